@@ -0,0 +1,251 @@
+//! The editor page: a CodeMirror editor on the `<textarea>`, its diagram in
+//! the `<output>`, and the source kept in the URL hash for sharing.
+
+use std::rc::Rc;
+use std::sync::Mutex;
+
+use js_sys::{Array, JsString, Object, Reflect, Uint8Array};
+use wasm_bindgen::prelude::*;
+use web_sys::{
+    Blob, BlobPropertyBag, Element, HtmlAnchorElement, HtmlInputElement, HtmlSelectElement, Url,
+};
+
+use crate::share::{pickle, unpickle, LoadAndGenError};
+use crate::{
+    draw, generate, parse_lossy, render_png, source_from_svg, Background, ParseError, PngOptions,
+    RenderOptions, SvgBackend, SvgSize,
+};
+
+/// How the diagram is shown in the `<output>`, as chosen with the zoom
+/// `<select>`: `fit` or a zoom factor.
+fn view(zoom: &HtmlSelectElement) -> SvgBackend {
+    match zoom.value().as_str() {
+        "fit" => SvgBackend::new().with_size(SvgSize::FitWidth),
+        zoom => SvgBackend::new().with_zoom(zoom.parse().unwrap_or(1.0)),
+    }
+}
+
+/// [`generate`], shown with `view`.
+fn generate_in(text: &str, view: SvgBackend) -> (String, Vec<ParseError>) {
+    let (document, errors) = parse_lossy(text);
+    let svg = draw(&document, &RenderOptions::default()).render(view.with_source(text));
+    (svg, errors)
+}
+
+fn pickle_and_gen(text: &str, view: SvgBackend) -> (String, String, Vec<ParseError>) {
+    let pickled = pickle(text);
+    let (svg, errors) = generate_in(text, view);
+
+    (pickled, svg, errors)
+}
+
+fn load_and_gen(
+    hash: &str,
+    view: SvgBackend,
+) -> Result<(String, String, Vec<ParseError>), LoadAndGenError> {
+    let text = unpickle(hash)?;
+    let (svg, errors) = generate_in(&text, view);
+
+    Ok((text, svg, errors))
+}
+
+/// Save `data` as the file `name`.
+fn download(data: &[u8], mime_type: &str, name: &str) -> Result<(), JsValue> {
+    let bag = BlobPropertyBag::new();
+    bag.set_type(mime_type);
+    let parts = Array::of1(&Uint8Array::from(data));
+    let blob = Blob::new_with_u8_array_sequence_and_options(&parts, &bag)?;
+    let url = Url::create_object_url_with_blob(&blob)?;
+
+    let a = web_sys::window()
+        .unwrap()
+        .document()
+        .unwrap()
+        .create_element("a")?
+        .dyn_into::<HtmlAnchorElement>()?;
+    a.set_href(&url);
+    a.set_download(name);
+    a.click();
+    Url::revoke_object_url(&url)
+}
+
+/// Save `text` drawn as an SVG, which keeps `text` to be opened again.
+fn download_svg(text: &str) -> Result<(), JsValue> {
+    let (svg, _) = generate(text);
+    download(svg.as_bytes(), "image/svg+xml", "diagram.svg")
+}
+
+/// Save `text` drawn as a PNG, at twice the size for high density screens.
+fn download_png(text: &str, transparent: bool) -> Result<(), JsValue> {
+    let (document, _) = parse_lossy(text);
+    let background = if transparent {
+        Background::Transparent
+    } else {
+        Background::Theme
+    };
+    let options = PngOptions::new()
+        .with_scale(2.0)
+        .with_background(background);
+    let png = render_png(&document, &RenderOptions::default(), &options)
+        .map_err(|e| js_sys::Error::new(&e.to_string()))?;
+    download(&png, "image/png", "diagram.png")
+}
+
+#[wasm_bindgen(module = "codemirror")]
+extern "C" {
+    type CodeMirror;
+
+    fn fromTextArea(element: &web_sys::Element, options: &JsValue) -> CodeMirror;
+
+    #[wasm_bindgen(method)]
+    fn on(this: &CodeMirror, when: &JsValue, f: &JsValue);
+
+    #[wasm_bindgen(method)]
+    fn getValue(this: &CodeMirror) -> String;
+
+    #[wasm_bindgen(method)]
+    fn setValue(this: &CodeMirror, value: &str);
+
+    #[wasm_bindgen(method)]
+    fn operation(this: &CodeMirror, f: &dyn Fn());
+
+    #[wasm_bindgen(method)]
+    fn addLineWidget(this: &CodeMirror, line: usize, e: &Element, options: &JsValue) -> Element;
+
+    #[wasm_bindgen(method)]
+    fn removeLineWidget(this: &CodeMirror, e: &Element);
+}
+
+#[wasm_bindgen(start)]
+pub fn start() -> Result<(), JsValue> {
+    #[cfg(debug_assertions)]
+    console_error_panic_hook::set_once();
+    wasm_logger::init(Default::default());
+
+    let window = web_sys::window().unwrap();
+    let document = window.document().unwrap();
+    let output = document.query_selector("output").unwrap().unwrap();
+    let download_svg_button = document
+        .query_selector(".export button[name=svg]")
+        .unwrap()
+        .unwrap();
+    let download_png_button = document
+        .query_selector(".export button[name=png]")
+        .unwrap()
+        .unwrap();
+    let transparent = document
+        .query_selector(".export input[name=transparent]")
+        .unwrap()
+        .unwrap()
+        .dyn_into::<HtmlInputElement>()?;
+    let zoom = document
+        .query_selector(".view select[name=zoom]")
+        .unwrap()
+        .unwrap()
+        .dyn_into::<HtmlSelectElement>()?;
+
+    let editor = {
+        let options = Object::new();
+        Reflect::set(&options, &JsString::from("lineNumbers"), &JsValue::TRUE).unwrap();
+        Reflect::set(&options, &JsString::from("lineWrapping"), &JsValue::TRUE).unwrap();
+        fromTextArea(
+            &document.query_selector("textarea").unwrap().unwrap(),
+            &options,
+        )
+    };
+    let editor = Rc::new(editor);
+
+    let editor3 = editor.clone();
+    let c = Closure::wrap(Box::new(move || {
+        if let Err(e) = download_svg(&editor3.getValue()) {
+            log::error!("{:?}", e);
+        }
+    }) as Box<dyn Fn()>);
+    download_svg_button.add_event_listener_with_callback("click", c.as_ref().unchecked_ref())?;
+    c.forget();
+
+    let editor3 = editor.clone();
+    let c = Closure::wrap(Box::new(move || {
+        if let Err(e) = download_png(&editor3.getValue(), transparent.checked()) {
+            log::error!("{:?}", e);
+        }
+    }) as Box<dyn Fn()>);
+    download_png_button.add_event_listener_with_callback("click", c.as_ref().unchecked_ref())?;
+    c.forget();
+
+    let editor2 = editor.clone();
+    let widgets: Rc<Mutex<Vec<Element>>> = Rc::new(Mutex::new(vec![]));
+    let document = Rc::new(document);
+    let update_annotations = move |infos: Vec<ParseError>| {
+        let editor = editor2.clone();
+        let widgets = widgets.clone();
+        let document = document.clone();
+        editor2.operation(&move || {
+            let mut widgets = widgets.lock().unwrap();
+            for widget in widgets.iter() {
+                editor.removeLineWidget(widget);
+            }
+            widgets.clear();
+
+            for info in &infos {
+                let message = &info.message;
+                let line = info.line();
+
+                let pre = document.create_element("pre").unwrap();
+                pre.set_text_content(Some(message));
+                let msg = document.create_element("div").unwrap();
+                msg.class_list().add_1("error-msg").unwrap();
+                msg.append_child(&pre).unwrap();
+
+                let opt = Object::new();
+                Reflect::set(&opt, &JsString::from("coverGutter"), &JsValue::TRUE).unwrap();
+                Reflect::set(&opt, &JsString::from("noHScroll"), &JsValue::TRUE).unwrap();
+                let widget = editor.addLineWidget(line - 1, &msg, &opt);
+                widgets.push(widget);
+            }
+        });
+    };
+
+    let hash = window.location().hash().unwrap();
+    if hash.len() > 1 {
+        let (text, svg, errors) = load_and_gen(&hash, view(&zoom)).unwrap();
+        editor.setValue(&text);
+        output.set_inner_html(&svg);
+        update_annotations(errors);
+    }
+
+    let editor3 = editor.clone();
+    let output2 = output.clone();
+    let zoom2 = zoom.clone();
+    let c = Closure::wrap(Box::new(move || {
+        let (svg, _) = generate_in(&editor3.getValue(), view(&zoom2));
+        output2.set_inner_html(&svg);
+    }) as Box<dyn Fn()>);
+    zoom.add_event_listener_with_callback("change", c.as_ref().unchecked_ref())?;
+    c.forget();
+
+    let c = Closure::wrap(Box::new(move |cm: CodeMirror, _| {
+        let text = cm.getValue();
+        // an SVG pasted or dropped in is replaced with the text it was drawn
+        // from, which fires this again
+        let start = text.trim_start();
+        if start.starts_with("<svg") || start.starts_with("<?xml") {
+            if let Ok(source) = source_from_svg(&text) {
+                cm.setValue(&source);
+                return;
+            }
+        }
+        let (pickled, svg, errors) = pickle_and_gen(&text, view(&zoom));
+        for e in &errors {
+            log::error!("{:?}", e);
+        }
+        update_annotations(errors);
+        window.location().set_hash(&pickled).unwrap();
+        output.set_inner_html(&svg);
+    }) as Box<dyn Fn(CodeMirror, JsValue)>);
+
+    editor.on(&JsString::from("change"), c.as_ref());
+    c.forget();
+
+    Ok(())
+}
@@ -0,0 +1,254 @@
+//! Advance widths of DejaVu Sans, in units of 1/2048 em, for the characters
+//! most diagrams use. Generated from DejaVuSans.ttf.
+
+pub(super) const UNITS_PER_EM: u16 = 2048;
+pub(super) const ASCENDER: i16 = 1901;
+pub(super) const DESCENDER: i16 = -483;
+/// of `0`, for narrow characters not listed
+pub(super) const DEFAULT_ADVANCE: u16 = 1303;
+
+#[rustfmt::skip]
+pub(super) const ADVANCES: &[(char, u16)] = &[
+    ('\u{20}', 651), ('\u{21}', 821), ('\u{22}', 942), ('\u{23}', 1716), ('\u{24}', 1303),
+    ('\u{25}', 1946), ('\u{26}', 1597), ('\u{27}', 563), ('\u{28}', 799), ('\u{29}', 799),
+    ('\u{2a}', 1024), ('\u{2b}', 1716), ('\u{2c}', 651), ('\u{2d}', 739), ('\u{2e}', 651),
+    ('\u{2f}', 690), ('\u{30}', 1303), ('\u{31}', 1303), ('\u{32}', 1303), ('\u{33}', 1303),
+    ('\u{34}', 1303), ('\u{35}', 1303), ('\u{36}', 1303), ('\u{37}', 1303), ('\u{38}', 1303),
+    ('\u{39}', 1303), ('\u{3a}', 690), ('\u{3b}', 690), ('\u{3c}', 1716), ('\u{3d}', 1716),
+    ('\u{3e}', 1716), ('\u{3f}', 1087), ('\u{40}', 2048), ('\u{41}', 1401), ('\u{42}', 1405),
+    ('\u{43}', 1430), ('\u{44}', 1577), ('\u{45}', 1294), ('\u{46}', 1178), ('\u{47}', 1587),
+    ('\u{48}', 1540), ('\u{49}', 604), ('\u{4a}', 604), ('\u{4b}', 1343), ('\u{4c}', 1141),
+    ('\u{4d}', 1767), ('\u{4e}', 1532), ('\u{4f}', 1612), ('\u{50}', 1235), ('\u{51}', 1612),
+    ('\u{52}', 1423), ('\u{53}', 1300), ('\u{54}', 1251), ('\u{55}', 1499), ('\u{56}', 1401),
+    ('\u{57}', 2025), ('\u{58}', 1403), ('\u{59}', 1251), ('\u{5a}', 1403), ('\u{5b}', 799),
+    ('\u{5c}', 690), ('\u{5d}', 799), ('\u{5e}', 1716), ('\u{5f}', 1024), ('\u{60}', 1024),
+    ('\u{61}', 1255), ('\u{62}', 1300), ('\u{63}', 1126), ('\u{64}', 1300), ('\u{65}', 1260),
+    ('\u{66}', 721), ('\u{67}', 1300), ('\u{68}', 1298), ('\u{69}', 569), ('\u{6a}', 569),
+    ('\u{6b}', 1186), ('\u{6c}', 569), ('\u{6d}', 1995), ('\u{6e}', 1298), ('\u{6f}', 1253),
+    ('\u{70}', 1300), ('\u{71}', 1300), ('\u{72}', 842), ('\u{73}', 1067), ('\u{74}', 803),
+    ('\u{75}', 1298), ('\u{76}', 1212), ('\u{77}', 1675), ('\u{78}', 1212), ('\u{79}', 1212),
+    ('\u{7a}', 1075), ('\u{7b}', 1303), ('\u{7c}', 690), ('\u{7d}', 1303), ('\u{7e}', 1716),
+    ('\u{a0}', 651), ('\u{a1}', 821), ('\u{a2}', 1303), ('\u{a3}', 1303), ('\u{a4}', 1303),
+    ('\u{a5}', 1303), ('\u{a6}', 690), ('\u{a7}', 1024), ('\u{a8}', 1024), ('\u{a9}', 2048),
+    ('\u{aa}', 965), ('\u{ab}', 1253), ('\u{ac}', 1716), ('\u{ad}', 739), ('\u{ae}', 2048),
+    ('\u{af}', 1024), ('\u{b0}', 1024), ('\u{b1}', 1716), ('\u{b2}', 821), ('\u{b3}', 821),
+    ('\u{b4}', 1024), ('\u{b5}', 1303), ('\u{b6}', 1303), ('\u{b7}', 651), ('\u{b8}', 1024),
+    ('\u{b9}', 821), ('\u{ba}', 965), ('\u{bb}', 1253), ('\u{bc}', 1985), ('\u{bd}', 1985),
+    ('\u{be}', 1985), ('\u{bf}', 1087), ('\u{c0}', 1401), ('\u{c1}', 1401), ('\u{c2}', 1401),
+    ('\u{c3}', 1401), ('\u{c4}', 1401), ('\u{c5}', 1401), ('\u{c6}', 1995), ('\u{c7}', 1430),
+    ('\u{c8}', 1294), ('\u{c9}', 1294), ('\u{ca}', 1294), ('\u{cb}', 1294), ('\u{cc}', 604),
+    ('\u{cd}', 604), ('\u{ce}', 604), ('\u{cf}', 604), ('\u{d0}', 1587), ('\u{d1}', 1532),
+    ('\u{d2}', 1612), ('\u{d3}', 1612), ('\u{d4}', 1612), ('\u{d5}', 1612), ('\u{d6}', 1612),
+    ('\u{d7}', 1716), ('\u{d8}', 1612), ('\u{d9}', 1499), ('\u{da}', 1499), ('\u{db}', 1499),
+    ('\u{dc}', 1499), ('\u{dd}', 1251), ('\u{de}', 1239), ('\u{df}', 1290), ('\u{e0}', 1255),
+    ('\u{e1}', 1255), ('\u{e2}', 1255), ('\u{e3}', 1255), ('\u{e4}', 1255), ('\u{e5}', 1255),
+    ('\u{e6}', 2011), ('\u{e7}', 1126), ('\u{e8}', 1260), ('\u{e9}', 1260), ('\u{ea}', 1260),
+    ('\u{eb}', 1260), ('\u{ec}', 569), ('\u{ed}', 569), ('\u{ee}', 569), ('\u{ef}', 569),
+    ('\u{f0}', 1253), ('\u{f1}', 1298), ('\u{f2}', 1253), ('\u{f3}', 1253), ('\u{f4}', 1253),
+    ('\u{f5}', 1253), ('\u{f6}', 1253), ('\u{f7}', 1716), ('\u{f8}', 1253), ('\u{f9}', 1298),
+    ('\u{fa}', 1298), ('\u{fb}', 1298), ('\u{fc}', 1298), ('\u{fd}', 1212), ('\u{fe}', 1300),
+    ('\u{ff}', 1212), ('\u{100}', 1401), ('\u{101}', 1255), ('\u{102}', 1401), ('\u{103}', 1255),
+    ('\u{104}', 1401), ('\u{105}', 1255), ('\u{106}', 1430), ('\u{107}', 1126), ('\u{108}', 1430),
+    ('\u{109}', 1126), ('\u{10a}', 1430), ('\u{10b}', 1126), ('\u{10c}', 1430), ('\u{10d}', 1126),
+    ('\u{10e}', 1577), ('\u{10f}', 1300), ('\u{110}', 1587), ('\u{111}', 1300), ('\u{112}', 1294),
+    ('\u{113}', 1260), ('\u{114}', 1294), ('\u{115}', 1260), ('\u{116}', 1294), ('\u{117}', 1260),
+    ('\u{118}', 1294), ('\u{119}', 1260), ('\u{11a}', 1294), ('\u{11b}', 1260), ('\u{11c}', 1587),
+    ('\u{11d}', 1300), ('\u{11e}', 1587), ('\u{11f}', 1300), ('\u{120}', 1587), ('\u{121}', 1300),
+    ('\u{122}', 1587), ('\u{123}', 1300), ('\u{124}', 1540), ('\u{125}', 1298), ('\u{126}', 1876),
+    ('\u{127}', 1423), ('\u{128}', 604), ('\u{129}', 569), ('\u{12a}', 604), ('\u{12b}', 569),
+    ('\u{12c}', 604), ('\u{12d}', 569), ('\u{12e}', 604), ('\u{12f}', 569), ('\u{130}', 604),
+    ('\u{131}', 569), ('\u{132}', 1208), ('\u{133}', 1138), ('\u{134}', 604), ('\u{135}', 569),
+    ('\u{136}', 1343), ('\u{137}', 1186), ('\u{138}', 1186), ('\u{139}', 1141), ('\u{13a}', 569),
+    ('\u{13b}', 1141), ('\u{13c}', 569), ('\u{13d}', 1141), ('\u{13e}', 768), ('\u{13f}', 1141),
+    ('\u{140}', 700), ('\u{141}', 1151), ('\u{142}', 582), ('\u{143}', 1532), ('\u{144}', 1298),
+    ('\u{145}', 1532), ('\u{146}', 1298), ('\u{147}', 1532), ('\u{148}', 1298), ('\u{149}', 1666),
+    ('\u{14a}', 1532), ('\u{14b}', 1298), ('\u{14c}', 1612), ('\u{14d}', 1253), ('\u{14e}', 1612),
+    ('\u{14f}', 1253), ('\u{150}', 1612), ('\u{151}', 1253), ('\u{152}', 2191), ('\u{153}', 2095),
+    ('\u{154}', 1423), ('\u{155}', 842), ('\u{156}', 1423), ('\u{157}', 842), ('\u{158}', 1423),
+    ('\u{159}', 842), ('\u{15a}', 1300), ('\u{15b}', 1067), ('\u{15c}', 1300), ('\u{15d}', 1067),
+    ('\u{15e}', 1300), ('\u{15f}', 1067), ('\u{160}', 1300), ('\u{161}', 1067), ('\u{162}', 1251),
+    ('\u{163}', 803), ('\u{164}', 1251), ('\u{165}', 803), ('\u{166}', 1251), ('\u{167}', 803),
+    ('\u{168}', 1499), ('\u{169}', 1298), ('\u{16a}', 1499), ('\u{16b}', 1298), ('\u{16c}', 1499),
+    ('\u{16d}', 1298), ('\u{16e}', 1499), ('\u{16f}', 1298), ('\u{170}', 1499), ('\u{171}', 1298),
+    ('\u{172}', 1499), ('\u{173}', 1298), ('\u{174}', 2025), ('\u{175}', 1675), ('\u{176}', 1251),
+    ('\u{177}', 1212), ('\u{178}', 1251), ('\u{179}', 1403), ('\u{17a}', 1075), ('\u{17b}', 1403),
+    ('\u{17c}', 1075), ('\u{17d}', 1403), ('\u{17e}', 1075), ('\u{17f}', 721), ('\u{180}', 1300),
+    ('\u{181}', 1505), ('\u{182}', 1405), ('\u{183}', 1300), ('\u{184}', 1405), ('\u{185}', 1300),
+    ('\u{186}', 1440), ('\u{187}', 1430), ('\u{188}', 1126), ('\u{189}', 1587), ('\u{18a}', 1677),
+    ('\u{18b}', 1405), ('\u{18c}', 1300), ('\u{18d}', 1253), ('\u{18e}', 1294), ('\u{18f}', 1612),
+    ('\u{190}', 1258), ('\u{191}', 1178), ('\u{192}', 721), ('\u{193}', 1587), ('\u{194}', 1406),
+    ('\u{195}', 2015), ('\u{196}', 724), ('\u{197}', 604), ('\u{198}', 1527), ('\u{199}', 1186),
+    ('\u{19a}', 569), ('\u{19b}', 1212), ('\u{19c}', 1995), ('\u{19d}', 1532), ('\u{19e}', 1298),
+    ('\u{19f}', 1612), ('\u{1a0}', 1870), ('\u{1a1}', 1253), ('\u{1a2}', 1943), ('\u{1a3}', 1555),
+    ('\u{1a4}', 1335), ('\u{1a5}', 1300), ('\u{1a6}', 1423), ('\u{1a7}', 1300), ('\u{1a8}', 1067),
+    ('\u{1a9}', 1294), ('\u{1aa}', 688), ('\u{1ab}', 803), ('\u{1ac}', 1251), ('\u{1ad}', 803),
+    ('\u{1ae}', 1251), ('\u{1af}', 1757), ('\u{1b0}', 1298), ('\u{1b1}', 1565), ('\u{1b2}', 1476),
+    ('\u{1b3}', 1523), ('\u{1b4}', 1496), ('\u{1b5}', 1403), ('\u{1b6}', 1075), ('\u{1b7}', 1364),
+    ('\u{1b8}', 1364), ('\u{1b9}', 1183), ('\u{1ba}', 1075), ('\u{1bb}', 1303), ('\u{1bc}', 1364),
+    ('\u{1bd}', 1183), ('\u{1be}', 1045), ('\u{1bf}', 1300), ('\u{1c0}', 604), ('\u{1c1}', 1008),
+    ('\u{1c2}', 940), ('\u{1c3}', 605), ('\u{1c4}', 2912), ('\u{1c5}', 2660), ('\u{1c6}', 2364),
+    ('\u{1c7}', 1711), ('\u{1c8}', 1611), ('\u{1c9}', 935), ('\u{1ca}', 1907), ('\u{1cb}', 1892),
+    ('\u{1cc}', 1633), ('\u{1cd}', 1401), ('\u{1ce}', 1255), ('\u{1cf}', 604), ('\u{1d0}', 569),
+    ('\u{1d1}', 1612), ('\u{1d2}', 1253), ('\u{1d3}', 1499), ('\u{1d4}', 1298), ('\u{1d5}', 1499),
+    ('\u{1d6}', 1298), ('\u{1d7}', 1499), ('\u{1d8}', 1298), ('\u{1d9}', 1499), ('\u{1da}', 1298),
+    ('\u{1db}', 1499), ('\u{1dc}', 1298), ('\u{1dd}', 1260), ('\u{1de}', 1401), ('\u{1df}', 1255),
+    ('\u{1e0}', 1401), ('\u{1e1}', 1255), ('\u{1e2}', 1995), ('\u{1e3}', 2011), ('\u{1e4}', 1587),
+    ('\u{1e5}', 1300), ('\u{1e6}', 1587), ('\u{1e7}', 1300), ('\u{1e8}', 1343), ('\u{1e9}', 1186),
+    ('\u{1ea}', 1612), ('\u{1eb}', 1253), ('\u{1ec}', 1612), ('\u{1ed}', 1253), ('\u{1ee}', 1364),
+    ('\u{1ef}', 1183), ('\u{1f0}', 569), ('\u{1f1}', 2912), ('\u{1f2}', 2660), ('\u{1f3}', 2364),
+    ('\u{1f4}', 1587), ('\u{1f5}', 1300), ('\u{1f6}', 2279), ('\u{1f7}', 1397), ('\u{1f8}', 1532),
+    ('\u{1f9}', 1298), ('\u{1fa}', 1401), ('\u{1fb}', 1255), ('\u{1fc}', 1995), ('\u{1fd}', 2011),
+    ('\u{1fe}', 1612), ('\u{1ff}', 1253), ('\u{200}', 1401), ('\u{201}', 1255), ('\u{202}', 1401),
+    ('\u{203}', 1255), ('\u{204}', 1294), ('\u{205}', 1260), ('\u{206}', 1294), ('\u{207}', 1260),
+    ('\u{208}', 604), ('\u{209}', 569), ('\u{20a}', 604), ('\u{20b}', 569), ('\u{20c}', 1612),
+    ('\u{20d}', 1253), ('\u{20e}', 1612), ('\u{20f}', 1253), ('\u{210}', 1423), ('\u{211}', 842),
+    ('\u{212}', 1423), ('\u{213}', 842), ('\u{214}', 1499), ('\u{215}', 1298), ('\u{216}', 1499),
+    ('\u{217}', 1298), ('\u{218}', 1300), ('\u{219}', 1067), ('\u{21a}', 1251), ('\u{21b}', 803),
+    ('\u{21c}', 1284), ('\u{21d}', 1068), ('\u{21e}', 1540), ('\u{21f}', 1298), ('\u{220}', 1506),
+    ('\u{221}', 1716), ('\u{222}', 1430), ('\u{223}', 1250), ('\u{224}', 1403), ('\u{225}', 1075),
+    ('\u{226}', 1401), ('\u{227}', 1255), ('\u{228}', 1294), ('\u{229}', 1260), ('\u{22a}', 1612),
+    ('\u{22b}', 1253), ('\u{22c}', 1612), ('\u{22d}', 1253), ('\u{22e}', 1612), ('\u{22f}', 1253),
+    ('\u{230}', 1612), ('\u{231}', 1253), ('\u{232}', 1251), ('\u{233}', 1212), ('\u{234}', 972),
+    ('\u{235}', 1726), ('\u{236}', 977), ('\u{237}', 569), ('\u{238}', 2044), ('\u{239}', 2044),
+    ('\u{23a}', 1401), ('\u{23b}', 1430), ('\u{23c}', 1126), ('\u{23d}', 1141), ('\u{23e}', 1251),
+    ('\u{23f}', 1067), ('\u{240}', 1075), ('\u{241}', 1235), ('\u{242}', 981), ('\u{243}', 1405),
+    ('\u{244}', 1499), ('\u{245}', 1401), ('\u{246}', 1294), ('\u{247}', 1260), ('\u{248}', 604),
+    ('\u{249}', 569), ('\u{24a}', 1600), ('\u{24b}', 1300), ('\u{24c}', 1423), ('\u{24d}', 842),
+    ('\u{24e}', 1251), ('\u{24f}', 1212), ('\u{370}', 1340), ('\u{371}', 1163), ('\u{372}', 1765),
+    ('\u{373}', 1326), ('\u{374}', 570), ('\u{375}', 570), ('\u{376}', 1532), ('\u{377}', 1331),
+    ('\u{37a}', 1024), ('\u{37b}', 1125), ('\u{37c}', 1126), ('\u{37d}', 1125), ('\u{37e}', 690),
+    ('\u{37f}', 604), ('\u{384}', 1024), ('\u{385}', 1024), ('\u{386}', 1418), ('\u{387}', 651),
+    ('\u{388}', 1528), ('\u{389}', 1784), ('\u{38a}', 836), ('\u{38c}', 1664), ('\u{38e}', 1689),
+    ('\u{38f}', 1691), ('\u{390}', 693), ('\u{391}', 1401), ('\u{392}', 1405), ('\u{393}', 1141),
+    ('\u{394}', 1401), ('\u{395}', 1294), ('\u{396}', 1403), ('\u{397}', 1540), ('\u{398}', 1612),
+    ('\u{399}', 604), ('\u{39a}', 1343), ('\u{39b}', 1401), ('\u{39c}', 1767), ('\u{39d}', 1532),
+    ('\u{39e}', 1294), ('\u{39f}', 1612), ('\u{3a0}', 1540), ('\u{3a1}', 1235), ('\u{3a3}', 1294),
+    ('\u{3a4}', 1251), ('\u{3a5}', 1251), ('\u{3a6}', 1612), ('\u{3a7}', 1403), ('\u{3a8}', 1612),
+    ('\u{3a9}', 1565), ('\u{3aa}', 604), ('\u{3ab}', 1251), ('\u{3ac}', 1350), ('\u{3ad}', 1107),
+    ('\u{3ae}', 1298), ('\u{3af}', 693), ('\u{3b0}', 1185), ('\u{3b1}', 1350), ('\u{3b2}', 1307),
+    ('\u{3b3}', 1212), ('\u{3b4}', 1253), ('\u{3b5}', 1107), ('\u{3b6}', 1114), ('\u{3b7}', 1298),
+    ('\u{3b8}', 1253), ('\u{3b9}', 693), ('\u{3ba}', 1207), ('\u{3bb}', 1212), ('\u{3bc}', 1303),
+    ('\u{3bd}', 1144), ('\u{3be}', 1142), ('\u{3bf}', 1253), ('\u{3c0}', 1233), ('\u{3c1}', 1300),
+    ('\u{3c2}', 1202), ('\u{3c3}', 1298), ('\u{3c4}', 1233), ('\u{3c5}', 1185), ('\u{3c6}', 1351),
+    ('\u{3c7}', 1183), ('\u{3c8}', 1351), ('\u{3c9}', 1715), ('\u{3ca}', 693), ('\u{3cb}', 1185),
+    ('\u{3cc}', 1253), ('\u{3cd}', 1185), ('\u{3ce}', 1715), ('\u{3cf}', 1343), ('\u{3d0}', 1258),
+    ('\u{3d1}', 1268), ('\u{3d2}', 1431), ('\u{3d3}', 1725), ('\u{3d4}', 1431), ('\u{3d5}', 1351),
+    ('\u{3d6}', 1715), ('\u{3d7}', 1359), ('\u{3d8}', 1612), ('\u{3d9}', 1253), ('\u{3da}', 1328),
+    ('\u{3db}', 1202), ('\u{3dc}', 1178), ('\u{3dd}', 939), ('\u{3de}', 1351), ('\u{3df}', 1351),
+    ('\u{3e0}', 1772), ('\u{3e1}', 1285), ('\u{3e2}', 1912), ('\u{3e3}', 1715), ('\u{3e4}', 1553),
+    ('\u{3e5}', 1350), ('\u{3e6}', 1621), ('\u{3e7}', 1259), ('\u{3e8}', 1406), ('\u{3e9}', 1243),
+    ('\u{3ea}', 1572), ('\u{3eb}', 1280), ('\u{3ec}', 1432), ('\u{3ed}', 1253), ('\u{3ee}', 1251),
+    ('\u{3ef}', 1098), ('\u{3f0}', 1359), ('\u{3f1}', 1300), ('\u{3f2}', 1126), ('\u{3f3}', 569),
+    ('\u{3f4}', 1612), ('\u{3f5}', 1260), ('\u{3f6}', 1260), ('\u{3f7}', 1239), ('\u{3f8}', 1300),
+    ('\u{3f9}', 1430), ('\u{3fa}', 1767), ('\u{3fb}', 1333), ('\u{3fc}', 1300), ('\u{3fd}', 1440),
+    ('\u{3fe}', 1430), ('\u{3ff}', 1440), ('\u{400}', 1294), ('\u{401}', 1294), ('\u{402}', 1610),
+    ('\u{403}', 1249), ('\u{404}', 1430), ('\u{405}', 1300), ('\u{406}', 604), ('\u{407}', 604),
+    ('\u{408}', 604), ('\u{409}', 2240), ('\u{40a}', 2140), ('\u{40b}', 1610), ('\u{40c}', 1454),
+    ('\u{40d}', 1532), ('\u{40e}', 1248), ('\u{40f}', 1540), ('\u{410}', 1401), ('\u{411}', 1405),
+    ('\u{412}', 1405), ('\u{413}', 1249), ('\u{414}', 1600), ('\u{415}', 1294), ('\u{416}', 2206),
+    ('\u{417}', 1313), ('\u{418}', 1532), ('\u{419}', 1532), ('\u{41a}', 1454), ('\u{41b}', 1540),
+    ('\u{41c}', 1767), ('\u{41d}', 1540), ('\u{41e}', 1612), ('\u{41f}', 1540), ('\u{420}', 1235),
+    ('\u{421}', 1430), ('\u{422}', 1251), ('\u{423}', 1248), ('\u{424}', 1763), ('\u{425}', 1403),
+    ('\u{426}', 1590), ('\u{427}', 1404), ('\u{428}', 2190), ('\u{429}', 2240), ('\u{42a}', 1705),
+    ('\u{42b}', 1807), ('\u{42c}', 1405), ('\u{42d}', 1430), ('\u{42e}', 2211), ('\u{42f}', 1423),
+    ('\u{430}', 1255), ('\u{431}', 1263), ('\u{432}', 1207), ('\u{433}', 1076), ('\u{434}', 1416),
+    ('\u{435}', 1260), ('\u{436}', 1845), ('\u{437}', 1089), ('\u{438}', 1331), ('\u{439}', 1331),
+    ('\u{43a}', 1237), ('\u{43b}', 1309), ('\u{43c}', 1545), ('\u{43d}', 1339), ('\u{43e}', 1253),
+    ('\u{43f}', 1339), ('\u{440}', 1300), ('\u{441}', 1126), ('\u{442}', 1193), ('\u{443}', 1212),
+    ('\u{444}', 1751), ('\u{445}', 1212), ('\u{446}', 1394), ('\u{447}', 1210), ('\u{448}', 1874),
+    ('\u{449}', 1929), ('\u{44a}', 1447), ('\u{44b}', 1617), ('\u{44c}', 1207), ('\u{44d}', 1124),
+    ('\u{44e}', 1724), ('\u{44f}', 1232), ('\u{450}', 1260), ('\u{451}', 1260), ('\u{452}', 1280),
+    ('\u{453}', 1076), ('\u{454}', 1124), ('\u{455}', 1067), ('\u{456}', 569), ('\u{457}', 569),
+    ('\u{458}', 569), ('\u{459}', 1848), ('\u{45a}', 1840), ('\u{45b}', 1335), ('\u{45c}', 1237),
+    ('\u{45d}', 1331), ('\u{45e}', 1212), ('\u{45f}', 1339), ('\u{460}', 1912), ('\u{461}', 1715),
+    ('\u{462}', 1578), ('\u{463}', 1376), ('\u{464}', 1930), ('\u{465}', 1534), ('\u{466}', 1801),
+    ('\u{467}', 1604), ('\u{468}', 2375), ('\u{469}', 2051), ('\u{46a}', 1612), ('\u{46b}', 1253),
+    ('\u{46c}', 2103), ('\u{46d}', 1688), ('\u{46e}', 1303), ('\u{46f}', 1107), ('\u{470}', 1754),
+    ('\u{471}', 1795), ('\u{472}', 1612), ('\u{473}', 1253), ('\u{474}', 1600), ('\u{475}', 1362),
+    ('\u{476}', 1600), ('\u{477}', 1362), ('\u{478}', 2032), ('\u{479}', 1852), ('\u{47a}', 1952),
+    ('\u{47b}', 1553), ('\u{47c}', 2416), ('\u{47d}', 2105), ('\u{47e}', 1912), ('\u{47f}', 1715),
+    ('\u{480}', 1430), ('\u{481}', 1126), ('\u{482}', 1029), ('\u{483}', 0), ('\u{484}', 0),
+    ('\u{485}', 0), ('\u{486}', 0), ('\u{487}', 0), ('\u{488}', 856), ('\u{489}', 856),
+    ('\u{48a}', 1582), ('\u{48b}', 1386), ('\u{48c}', 1405), ('\u{48d}', 1207), ('\u{48e}', 1235),
+    ('\u{48f}', 1300), ('\u{490}', 1249), ('\u{491}', 1076), ('\u{492}', 1382), ('\u{493}', 1209),
+    ('\u{494}', 1278), ('\u{495}', 1085), ('\u{496}', 2206), ('\u{497}', 1845), ('\u{498}', 1313),
+    ('\u{499}', 1089), ('\u{49a}', 1454), ('\u{49b}', 1237), ('\u{49c}', 1454), ('\u{49d}', 1237),
+    ('\u{49e}', 1454), ('\u{49f}', 1237), ('\u{4a0}', 1754), ('\u{4a1}', 1703), ('\u{4a2}', 1540),
+    ('\u{4a3}', 1353), ('\u{4a4}', 2077), ('\u{4a5}', 1796), ('\u{4a6}', 2214), ('\u{4a7}', 1875),
+    ('\u{4a8}', 1798), ('\u{4a9}', 1419), ('\u{4aa}', 1430), ('\u{4ab}', 1126), ('\u{4ac}', 1251),
+    ('\u{4ad}', 1193), ('\u{4ae}', 1251), ('\u{4af}', 1212), ('\u{4b0}', 1251), ('\u{4b1}', 1212),
+    ('\u{4b2}', 1403), ('\u{4b3}', 1212), ('\u{4b4}', 1913), ('\u{4b5}', 1652), ('\u{4b6}', 1404),
+    ('\u{4b7}', 1210), ('\u{4b8}', 1404), ('\u{4b9}', 1210), ('\u{4ba}', 1404), ('\u{4bb}', 1298),
+    ('\u{4bc}', 1927), ('\u{4bd}', 1491), ('\u{4be}', 1927), ('\u{4bf}', 1491), ('\u{4c0}', 604),
+    ('\u{4c1}', 2206), ('\u{4c2}', 1845), ('\u{4c3}', 1343), ('\u{4c4}', 1237), ('\u{4c5}', 1589),
+    ('\u{4c6}', 1373), ('\u{4c7}', 1540), ('\u{4c8}', 1353), ('\u{4c9}', 1590), ('\u{4ca}', 1394),
+    ('\u{4cb}', 1404), ('\u{4cc}', 1210), ('\u{4cd}', 1818), ('\u{4ce}', 1586), ('\u{4cf}', 569),
+    ('\u{4d0}', 1401), ('\u{4d1}', 1255), ('\u{4d2}', 1401), ('\u{4d3}', 1255), ('\u{4d4}', 1995),
+    ('\u{4d5}', 2011), ('\u{4d6}', 1294), ('\u{4d7}', 1260), ('\u{4d8}', 1612), ('\u{4d9}', 1260),
+    ('\u{4da}', 1612), ('\u{4db}', 1260), ('\u{4dc}', 2206), ('\u{4dd}', 1845), ('\u{4de}', 1313),
+    ('\u{4df}', 1089), ('\u{4e0}', 1364), ('\u{4e1}', 1183), ('\u{4e2}', 1532), ('\u{4e3}', 1331),
+    ('\u{4e4}', 1532), ('\u{4e5}', 1331), ('\u{4e6}', 1612), ('\u{4e7}', 1253), ('\u{4e8}', 1612),
+    ('\u{4e9}', 1253), ('\u{4ea}', 1612), ('\u{4eb}', 1253), ('\u{4ec}', 1430), ('\u{4ed}', 1124),
+    ('\u{4ee}', 1248), ('\u{4ef}', 1212), ('\u{4f0}', 1248), ('\u{4f1}', 1212), ('\u{4f2}', 1248),
+    ('\u{4f3}', 1212), ('\u{4f4}', 1404), ('\u{4f5}', 1210), ('\u{4f6}', 1249), ('\u{4f7}', 1076),
+    ('\u{4f8}', 1807), ('\u{4f9}', 1617), ('\u{4fa}', 1382), ('\u{4fb}', 1209), ('\u{4fc}', 1403),
+    ('\u{4fd}', 1212), ('\u{4fe}', 1403), ('\u{4ff}', 1212), ('\u{2000}', 1024), ('\u{2001}', 2048),
+    ('\u{2002}', 1024), ('\u{2003}', 2048), ('\u{2004}', 675), ('\u{2005}', 512), ('\u{2006}', 342),
+    ('\u{2007}', 1303), ('\u{2008}', 651), ('\u{2009}', 409), ('\u{200a}', 204), ('\u{200b}', 0),
+    ('\u{200c}', 0), ('\u{200d}', 0), ('\u{200e}', 0), ('\u{200f}', 0), ('\u{2010}', 739),
+    ('\u{2011}', 739), ('\u{2012}', 1303), ('\u{2013}', 1024), ('\u{2014}', 2048),
+    ('\u{2015}', 2048), ('\u{2016}', 1024), ('\u{2017}', 1024), ('\u{2018}', 651),
+    ('\u{2019}', 651), ('\u{201a}', 651), ('\u{201b}', 651), ('\u{201c}', 1061), ('\u{201d}', 1061),
+    ('\u{201e}', 1061), ('\u{201f}', 1061), ('\u{2020}', 1024), ('\u{2021}', 1024),
+    ('\u{2022}', 1208), ('\u{2023}', 1208), ('\u{2024}', 685), ('\u{2025}', 1367),
+    ('\u{2026}', 2048), ('\u{2027}', 651), ('\u{2028}', 0), ('\u{2029}', 0), ('\u{202a}', 0),
+    ('\u{202b}', 0), ('\u{202c}', 0), ('\u{202d}', 0), ('\u{202e}', 0), ('\u{202f}', 409),
+    ('\u{2030}', 2748), ('\u{2031}', 3554), ('\u{2032}', 465), ('\u{2033}', 765),
+    ('\u{2034}', 1065), ('\u{2035}', 465), ('\u{2036}', 765), ('\u{2037}', 1065), ('\u{2038}', 694),
+    ('\u{2039}', 819), ('\u{203a}', 819), ('\u{203b}', 1716), ('\u{203c}', 994), ('\u{203d}', 1087),
+    ('\u{203e}', 1024), ('\u{203f}', 1646), ('\u{2040}', 1646), ('\u{2041}', 512),
+    ('\u{2042}', 2048), ('\u{2043}', 1024), ('\u{2044}', 342), ('\u{2045}', 799), ('\u{2046}', 799),
+    ('\u{2047}', 1888), ('\u{2048}', 1501), ('\u{2049}', 1501), ('\u{204a}', 1018),
+    ('\u{204b}', 1303), ('\u{204c}', 1024), ('\u{204d}', 1024), ('\u{204e}', 1024),
+    ('\u{204f}', 690), ('\u{2050}', 1646), ('\u{2051}', 1024), ('\u{2052}', 921),
+    ('\u{2053}', 2048), ('\u{2054}', 1646), ('\u{2055}', 1716), ('\u{2056}', 1200),
+    ('\u{2057}', 1358), ('\u{2058}', 1716), ('\u{2059}', 1716), ('\u{205a}', 651),
+    ('\u{205b}', 1633), ('\u{205c}', 1716), ('\u{205d}', 651), ('\u{205e}', 651), ('\u{205f}', 455),
+    ('\u{2060}', 0), ('\u{2061}', 0), ('\u{2062}', 0), ('\u{2063}', 0), ('\u{2064}', 0),
+    ('\u{206a}', 0), ('\u{206b}', 0), ('\u{206c}', 0), ('\u{206d}', 0), ('\u{206e}', 0),
+    ('\u{206f}', 0), ('\u{20a0}', 1796), ('\u{20a1}', 1303), ('\u{20a2}', 1303), ('\u{20a3}', 1303),
+    ('\u{20a4}', 1303), ('\u{20a5}', 1995), ('\u{20a6}', 1303), ('\u{20a7}', 2606),
+    ('\u{20a8}', 2199), ('\u{20a9}', 2025), ('\u{20aa}', 1606), ('\u{20ab}', 1303),
+    ('\u{20ac}', 1303), ('\u{20ad}', 1303), ('\u{20ae}', 1303), ('\u{20af}', 2606),
+    ('\u{20b0}', 1303), ('\u{20b1}', 1303), ('\u{20b2}', 1303), ('\u{20b3}', 1303),
+    ('\u{20b4}', 1585), ('\u{20b5}', 1303), ('\u{20b8}', 1303), ('\u{20b9}', 1303),
+    ('\u{20ba}', 1303), ('\u{20bd}', 1303), ('\u{2190}', 1716), ('\u{2191}', 1716),
+    ('\u{2192}', 1716), ('\u{2193}', 1716), ('\u{2194}', 1716), ('\u{2195}', 1716),
+    ('\u{2196}', 1716), ('\u{2197}', 1716), ('\u{2198}', 1716), ('\u{2199}', 1716),
+    ('\u{219a}', 1716), ('\u{219b}', 1716), ('\u{219c}', 1716), ('\u{219d}', 1716),
+    ('\u{219e}', 1716), ('\u{219f}', 1716), ('\u{21a0}', 1716), ('\u{21a1}', 1716),
+    ('\u{21a2}', 1716), ('\u{21a3}', 1716), ('\u{21a4}', 1716), ('\u{21a5}', 1716),
+    ('\u{21a6}', 1716), ('\u{21a7}', 1716), ('\u{21a8}', 1716), ('\u{21a9}', 1716),
+    ('\u{21aa}', 1716), ('\u{21ab}', 1716), ('\u{21ac}', 1716), ('\u{21ad}', 1716),
+    ('\u{21ae}', 1716), ('\u{21af}', 1716), ('\u{21b0}', 1716), ('\u{21b1}', 1716),
+    ('\u{21b2}', 1716), ('\u{21b3}', 1716), ('\u{21b4}', 1716), ('\u{21b5}', 1716),
+    ('\u{21b6}', 1716), ('\u{21b7}', 1716), ('\u{21b8}', 1716), ('\u{21b9}', 1716),
+    ('\u{21ba}', 1716), ('\u{21bb}', 1716), ('\u{21bc}', 1716), ('\u{21bd}', 1716),
+    ('\u{21be}', 1716), ('\u{21bf}', 1716), ('\u{21c0}', 1716), ('\u{21c1}', 1716),
+    ('\u{21c2}', 1716), ('\u{21c3}', 1716), ('\u{21c4}', 1716), ('\u{21c5}', 1716),
+    ('\u{21c6}', 1716), ('\u{21c7}', 1716), ('\u{21c8}', 1716), ('\u{21c9}', 1716),
+    ('\u{21ca}', 1716), ('\u{21cb}', 1716), ('\u{21cc}', 1716), ('\u{21cd}', 1716),
+    ('\u{21ce}', 1716), ('\u{21cf}', 1716), ('\u{21d0}', 1716), ('\u{21d1}', 1716),
+    ('\u{21d2}', 1716), ('\u{21d3}', 1716), ('\u{21d4}', 1716), ('\u{21d5}', 1716),
+    ('\u{21d6}', 1716), ('\u{21d7}', 1716), ('\u{21d8}', 1716), ('\u{21d9}', 1716),
+    ('\u{21da}', 1716), ('\u{21db}', 1716), ('\u{21dc}', 1716), ('\u{21dd}', 1716),
+    ('\u{21de}', 1716), ('\u{21df}', 1716), ('\u{21e0}', 1716), ('\u{21e1}', 1716),
+    ('\u{21e2}', 1716), ('\u{21e3}', 1716), ('\u{21e4}', 1716), ('\u{21e5}', 1716),
+    ('\u{21e6}', 1716), ('\u{21e7}', 1716), ('\u{21e8}', 1716), ('\u{21e9}', 1716),
+    ('\u{21ea}', 1716), ('\u{21eb}', 1716), ('\u{21ec}', 1716), ('\u{21ed}', 1716),
+    ('\u{21ee}', 1716), ('\u{21ef}', 1716), ('\u{21f0}', 1716), ('\u{21f1}', 1716),
+    ('\u{21f2}', 1716), ('\u{21f3}', 1716), ('\u{21f4}', 1716), ('\u{21f5}', 1716),
+    ('\u{21f6}', 1716), ('\u{21f7}', 1716), ('\u{21f8}', 1716), ('\u{21f9}', 1716),
+    ('\u{21fa}', 1716), ('\u{21fb}', 1716), ('\u{21fc}', 1716), ('\u{21fd}', 1716),
+    ('\u{21fe}', 1716), ('\u{21ff}', 1716),
+];
@@ -0,0 +1,206 @@
+//! Glyph advance widths, to size boxes to the text drawn in them.
+
+mod dejavu_sans;
+
+use std::sync::Arc;
+
+use thiserror::Error;
+use unicode_width::UnicodeWidthChar;
+
+/// A font file that could not be read.
+#[derive(Debug, Error)]
+#[error("cannot read font: {0}")]
+pub struct FontError(#[from] ttf_parser::FaceParsingError);
+
+/// How wide the characters of a font are and how tall its lines.
+///
+/// Characters the font has no glyph for are measured as the browser would
+/// most likely draw them from a fallback font: wide (CJK, emoji) characters
+/// one em, combining and control characters nothing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FontMetrics {
+    family: Option<String>,
+    units_per_em: u16,
+    ascender: i16,
+    descender: i16,
+    line_gap: i16,
+    /// sorted by character
+    advances: Arc<[(char, u16)]>,
+    /// of characters not in `advances`
+    default_advance: u16,
+}
+
+impl Default for FontMetrics {
+    fn default() -> Self {
+        Self::dejavu_sans()
+    }
+}
+
+impl FontMetrics {
+    /// DejaVu Sans, bundled for Latin, Greek, Cyrillic and common symbols.
+    pub fn dejavu_sans() -> Self {
+        Self {
+            family: Some("DejaVu Sans".to_string()),
+            units_per_em: dejavu_sans::UNITS_PER_EM,
+            ascender: dejavu_sans::ASCENDER,
+            descender: dejavu_sans::DESCENDER,
+            line_gap: 0,
+            advances: dejavu_sans::ADVANCES.into(),
+            default_advance: dejavu_sans::DEFAULT_ADVANCE,
+        }
+    }
+
+    /// DejaVu Sans Mono, where every narrow character is 0.6 em wide.
+    pub fn dejavu_sans_mono() -> Self {
+        Self {
+            family: Some("DejaVu Sans Mono".to_string()),
+            advances: Arc::new([]),
+            default_advance: 1233,
+            ..Self::dejavu_sans()
+        }
+    }
+
+    /// Bundled metrics for the first family of a CSS `font-family` list, if
+    /// there are any.
+    pub fn named(font_family: &str) -> Option<Self> {
+        let family = font_family.split(',').next()?.trim();
+        let family = family
+            .trim_matches(|c| c == '"' || c == '\'')
+            .to_lowercase();
+        match family.as_str() {
+            "dejavu sans" | "sans-serif" => Some(Self::dejavu_sans()),
+            "dejavu sans mono" | "monospace" => Some(Self::dejavu_sans_mono()),
+            _ => None,
+        }
+    }
+
+    /// Read the metrics of the first face of a TrueType or OpenType font.
+    pub fn from_font(data: &[u8]) -> Result<Self, FontError> {
+        let face = ttf_parser::Face::parse(data, 0)?;
+
+        let mut advances = vec![];
+        if let Some(cmap) = face.tables().cmap {
+            for subtable in cmap.subtables.into_iter().filter(|s| s.is_unicode()) {
+                subtable.codepoints(|codepoint| {
+                    let advance = char::from_u32(codepoint)
+                        .and_then(|c| Some((c, subtable.glyph_index(codepoint)?)))
+                        .and_then(|(c, glyph)| Some((c, face.glyph_hor_advance(glyph)?)));
+                    advances.extend(advance);
+                });
+            }
+        }
+        advances.sort_unstable();
+        advances.dedup_by_key(|(c, _)| *c);
+
+        let family = face
+            .names()
+            .into_iter()
+            .filter(|name| name.name_id == ttf_parser::name_id::FAMILY)
+            .find_map(|name| name.to_string());
+        let default_advance = face
+            .glyph_index('0')
+            .and_then(|glyph| face.glyph_hor_advance(glyph))
+            .unwrap_or(face.units_per_em() / 2);
+
+        Ok(Self {
+            family,
+            units_per_em: face.units_per_em(),
+            ascender: face.ascender(),
+            descender: face.descender(),
+            line_gap: face.line_gap(),
+            advances: advances.into(),
+            default_advance,
+        })
+    }
+
+    /// The family name of the font, for `font-family`.
+    pub fn family(&self) -> Option<&str> {
+        self.family.as_deref()
+    }
+
+    fn scale(&self, units: f32, size: isize) -> f32 {
+        units * size as f32 / self.units_per_em as f32
+    }
+
+    fn advance(&self, c: char) -> u16 {
+        match self.advances.binary_search_by_key(&c, |(c, _)| *c) {
+            Ok(i) => self.advances[i].1,
+            Err(_) => match c.width() {
+                Some(0) | None => 0,
+                Some(1) => self.default_advance,
+                Some(_) => self.units_per_em,
+            },
+        }
+    }
+
+    /// Width of a single line of text at `size` pixels.
+    pub(crate) fn width(&self, line: &str, size: isize) -> f32 {
+        let units = line.chars().map(|c| self.advance(c) as f32).sum();
+        self.scale(units, size)
+    }
+
+    /// Distance from the top of a line to its baseline.
+    pub(crate) fn ascent(&self, size: isize) -> f32 {
+        self.scale(self.ascender as f32, size)
+    }
+
+    /// Distance from the baseline to the bottom of a line.
+    pub(crate) fn descent(&self, size: isize) -> f32 {
+        self.scale(-self.descender as f32, size)
+    }
+
+    /// Distance from one baseline to the next.
+    pub(crate) fn line_height(&self, size: isize) -> f32 {
+        self.ascent(size) + self.descent(size) + self.scale(self.line_gap as f32, size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEJAVU_SANS: &[u8] = include_bytes!("../../fonts/DejaVuSans.ttf");
+    const DEJAVU_SANS_MONO: &[u8] = include_bytes!("../../fonts/DejaVuSansMono.ttf");
+
+    #[test]
+    fn bundled_metrics_match_the_fonts() {
+        let bundled = FontMetrics::dejavu_sans();
+        let font = FontMetrics::from_font(DEJAVU_SANS).unwrap();
+        assert_eq!(font.family(), Some("DejaVu Sans"));
+        for text in ["Hello, World!", "iiii WWWW", "Ωμέγα Привет", "x → y ±1"] {
+            assert_eq!(bundled.width(text, 16), font.width(text, 16), "{}", text);
+        }
+        assert_eq!(bundled.line_height(16), font.line_height(16));
+
+        let bundled = FontMetrics::dejavu_sans_mono();
+        let font = FontMetrics::from_font(DEJAVU_SANS_MONO).unwrap();
+        assert_eq!(bundled.width("iW.m", 16), font.width("iW.m", 16));
+    }
+
+    #[test]
+    fn proportional_widths() {
+        let metrics = FontMetrics::dejavu_sans();
+        assert!(metrics.width("i", 16) < metrics.width("W", 16));
+        assert_eq!(metrics.width("", 16), 0.0);
+        assert_eq!(metrics.width("WW", 16), metrics.width("W", 32));
+    }
+
+    #[test]
+    fn characters_without_glyphs() {
+        let metrics = FontMetrics::dejavu_sans();
+        // one em for wide characters, nothing for combining ones
+        assert_eq!(metrics.width("日本", 16), 32.0);
+        assert_eq!(metrics.width("e\u{301}", 16), metrics.width("e", 16));
+    }
+
+    #[test]
+    fn named_families() {
+        let named = |family| FontMetrics::named(family).and_then(|m| m.family.clone());
+        assert_eq!(
+            named("'DejaVu Sans', Arial"),
+            Some("DejaVu Sans".to_string())
+        );
+        assert_eq!(named("monospace"), Some("DejaVu Sans Mono".to_string()));
+        assert_eq!(named("Helvetica, sans-serif"), None);
+    }
+}
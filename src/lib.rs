@@ -1,70 +1,183 @@
+//! Hand-drawn looking sequence diagrams from a small text language.
+//!
+//! ```
+//! let document = seqdia::parse("Alice -> Bob: hello").unwrap();
+//! let svg = seqdia::render(&document, &seqdia::RenderOptions::default());
+//! assert!(svg.starts_with("<svg"));
+//! ```
+//!
+//! [`parse`] gives a [`Document`] that borrows from the text; call
+//! [`Document::into_owned`] to keep it around longer. Write a [`Visitor`] to
+//! walk it.
+//!
+//! [`draw`] lays a document out as a [`Paper`] of shapes and text. Implement
+//! [`Backend`] to write one out in another format than SVG.
+
+#[cfg(feature = "editor")]
+mod editor;
+mod font;
 mod paper;
 mod parse;
-
+#[cfg(feature = "pdf")]
+mod pdf;
+#[cfg(feature = "png")]
+mod png;
+#[cfg(feature = "wasm")]
+mod share;
+mod source;
+mod text;
+mod theme;
+#[cfg(feature = "wasm")]
+mod wasm;
+
+use std::borrow::Cow;
 use std::fmt;
 use std::hash::Hasher as _;
-use std::rc::Rc;
-use std::sync::Mutex;
-
-use base64::Engine as _;
-use base64::engine::GeneralPurpose;
-use base64::engine::general_purpose::URL_SAFE_NO_PAD;
-use js_sys::{JsString, Object, Reflect};
-use lz4_compression::prelude::{compress, decompress};
-use pest::error::LineColLocation;
+
+use pest::error::{InputLocation, LineColLocation};
 use thiserror::Error;
-use unicode_width::UnicodeWidthStr;
-use wasm_bindgen::prelude::*;
-use web_sys::Element;
 
-use paper::{MarkerEnd, Paper, Path, Rect, Text, TextAnchor};
-use parse::{
-    Actor, ArrowType, Document, LineType, Note, Participant, Signal, Statement, Title, Visitor,
+pub use font::{FontError, FontMetrics};
+pub use paper::{
+    Backend, Element, Fill, Group, Marker, Paper, Path, Point, Rect, Segment, Stroke, SvgBackend,
+    SvgSize, Text, TextAnchor, TextStyle,
 };
-
-const FONT_SIZE: isize = 16; // FIXME
+pub use parse::{
+    Activate, Activation, Actor, ArrowType, Autonumber, Deactivate, Document, DocumentIter,
+    Fragment, FragmentKind, LineType, Note, OwnedDocument, Participant, Section, Signal,
+    SignalType, Span, Statement, Style, StyleKind, ThemeDirective, Title, Visitor,
+};
+#[cfg(feature = "pdf")]
+pub use pdf::{render_pdf, PdfError, PdfOptions};
+#[cfg(feature = "png")]
+pub use png::{render_png, Background, PngError, PngOptions};
+pub use source::{source_from_svg, SourceError};
+pub use text::{render_text, TextOptions};
+pub use theme::Theme;
 
 const DIAGRAM_MARGIN: isize = 10;
 
 const ACTOR_MARGIN: isize = 10;
-const ACTOR_PADDING: isize = 10;
 
 const SIGNAL_MARGIN: isize = 10;
-const SIGNAL_PADDING: isize = 10;
 
-/*
 const NOTE_MARGIN: isize = 10;
-const NOTE_PADDING: isize = 5;
 const NOTE_OVERLAP: isize = 15;
-*/
 
 const TITLE_MARGIN: isize = 0;
-const TITLE_PADDING: isize = 5;
 
 const SELF_SIGNAL_WIDTH: isize = 20;
 
-const ENCODER: GeneralPurpose = URL_SAFE_NO_PAD;
+const ACTIVATION_WIDTH: isize = 10;
+
+const FRAGMENT_MARGIN: isize = 10;
+const FRAGMENT_PADDING: isize = 10;
+const FRAGMENT_TAG_PADDING: isize = 5;
+
+const ROUNDED_RADIUS: f32 = 6.0;
+
+/// How far below the top of a row a page may end, between the arrow of the
+/// row above and whatever is drawn in this one.
+const ROW_BREAK_OFFSET: isize = 8;
+
+fn text_bbox(text: &str, theme: &Theme) -> Rectangle {
+    let metrics = &theme.font_metrics;
+    let size = theme.font_size;
+    let width = text
+        .lines()
+        .map(|line| metrics.width(line, size))
+        .fold(0.0, f32::max);
+    let lines = text.lines().count().max(1);
+    let height = metrics.ascent(size)
+        + metrics.descent(size)
+        + (lines - 1) as f32 * metrics.line_height(size);
+    Rectangle::new(0, 0, width.ceil() as isize, height.ceil() as isize)
+}
 
-fn text_bbox(text: &str) -> Rectangle {
-    let width =
-        UnicodeWidthStr::width(text) as isize * (FONT_SIZE / 2) + ((text.len() / 5 * 6) as isize);
-    Rectangle::new(0, 0, width, FONT_SIZE)
+/// `text` with lines longer than the theme's `max_text_width` broken where
+/// the Unicode line breaking rules allow, between words or between the
+/// characters of languages written without spaces. A word longer than the
+/// width is left as it is.
+fn wrap_text<'t>(text: &'t str, theme: &Theme) -> Cow<'t, str> {
+    let max = match theme.max_text_width {
+        Some(max) => max as f32,
+        None => return Cow::Borrowed(text),
+    };
+    let width = |line: &str| theme.font_metrics.width(line.trim_end(), theme.font_size);
+    if text.lines().all(|line| width(line) <= max) {
+        return Cow::Borrowed(text);
+    }
+
+    let mut wrapped = String::new();
+    for (n, line) in text.lines().enumerate() {
+        if n > 0 {
+            wrapped.push('\n');
+        }
+        let mut start = 0;
+        let mut end = 0;
+        for (at, opportunity) in unicode_linebreak::linebreaks(line) {
+            let mandatory = opportunity == unicode_linebreak::BreakOpportunity::Mandatory;
+            if end > start && width(&line[start..at]) > max {
+                wrapped.push_str(line[start..end].trim_end());
+                wrapped.push('\n');
+                start = end;
+            }
+            end = at;
+            if mandatory && at < line.len() {
+                wrapped.push_str(line[start..end].trim_end());
+                wrapped.push('\n');
+                start = end;
+            }
+        }
+        wrapped.push_str(line[start..].trim_end());
+    }
+    Cow::Owned(wrapped)
 }
 
+/// A line of the input that could not be parsed.
 #[derive(Debug, Error, Clone)]
-struct ParseError {
-    line: Option<usize>,
+pub struct ParseError {
+    span: Span,
+    reason: String,
     message: String,
 }
 
+impl ParseError {
+    /// Where the error is in the source text.
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    /// 1-based line the error starts on.
+    pub fn line(&self) -> usize {
+        self.span.start_line_col().0
+    }
+
+    /// 1-based column, in characters, the error starts on.
+    pub fn column(&self) -> usize {
+        self.span.start_line_col().1
+    }
+
+    /// What went wrong, without the location and the source excerpt that
+    /// `Display` adds.
+    pub fn reason(&self) -> &str {
+        &self.reason
+    }
+}
+
 impl From<pest::error::Error<parse::Rule>> for ParseError {
     fn from(v: pest::error::Error<parse::Rule>) -> Self {
-        let line = match v.line_col {
-            LineColLocation::Pos((line, _)) => line,
-            LineColLocation::Span((line, _), _) => line,
+        let (start, end) = match v.location {
+            InputLocation::Pos(pos) => (pos, pos),
+            InputLocation::Span(span) => span,
+        };
+        let (start_line_col, end_line_col) = match v.line_col {
+            LineColLocation::Pos(pos) => (pos, pos),
+            LineColLocation::Span(start, end) => (start, end),
         };
         Self {
-            line: Some(line),
+            span: Span::new(start, end, start_line_col, end_line_col),
+            reason: v.variant.message().into_owned(),
             message: v.to_string(),
         }
     }
@@ -76,6 +189,74 @@ impl fmt::Display for ParseError {
     }
 }
 
+/// Every error found while parsing, in source order.
+#[derive(Debug, Error, Clone)]
+pub struct Diagnostics(Vec<ParseError>);
+
+impl Diagnostics {
+    pub fn iter(&self) -> std::slice::Iter<'_, ParseError> {
+        self.0.iter()
+    }
+}
+
+impl fmt::Display for Diagnostics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (n, error) in self.0.iter().enumerate() {
+            if n > 0 {
+                writeln!(f)?;
+            }
+            error.fmt(f)?;
+        }
+        Ok(())
+    }
+}
+
+impl IntoIterator for Diagnostics {
+    type Item = ParseError;
+    type IntoIter = std::vec::IntoIter<ParseError>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Diagnostics {
+    type Item = &'a ParseError;
+    type IntoIter = std::slice::Iter<'a, ParseError>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl From<Diagnostics> for Vec<ParseError> {
+    fn from(v: Diagnostics) -> Vec<ParseError> {
+        v.0
+    }
+}
+
+/// How [`render`] draws a document.
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct RenderOptions {
+    style: StyleKind,
+    theme: Theme,
+}
+
+impl RenderOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Used unless the document has a `style` directive.
+    pub fn with_style(self, style: StyleKind) -> Self {
+        Self { style, ..self }
+    }
+
+    /// Changed by the `theme` directives of the document, if any.
+    pub fn with_theme(self, theme: Theme) -> Self {
+        Self { theme, ..self }
+    }
+}
+
 #[derive(Debug)]
 struct LayoutTitle;
 
@@ -83,6 +264,8 @@ impl<'i> Visitor<'i> for LayoutTitle {
     type Output = ();
     type Context = Layout<'i>;
 
+    fn default_action(&self, _ctx: &mut Self::Context) -> Self::Output {}
+
     fn visit_document(&self, document: &Document<'i>, ctx: &mut Self::Context) -> Self::Output {
         for statement in document {
             statement.accept(self, ctx)
@@ -95,34 +278,42 @@ impl<'i> Visitor<'i> for LayoutTitle {
             Statement::Signal(signal) => signal.accept(self, ctx),
             Statement::Participant(participant) => participant.accept(self, ctx),
             Statement::Note(note) => note.accept(self, ctx),
+            Statement::Activate(activate) => activate.accept(self, ctx),
+            Statement::Deactivate(deactivate) => deactivate.accept(self, ctx),
+            Statement::Fragment(fragment) => fragment.accept(self, ctx),
+            Statement::Autonumber(autonumber) => autonumber.accept(self, ctx),
+            Statement::Style(style) => style.accept(self, ctx),
+            Statement::Theme(theme) => theme.accept(self, ctx),
         }
     }
 
     fn visit_title(&self, title: &Title<'i>, ctx: &mut Self::Context) -> Self::Output {
         if ctx.title.is_none() {
-            let bbox = text_bbox(&title.as_ref());
+            let bbox = text_bbox(title.as_ref(), &ctx.theme);
             ctx.title = Some((
                 title.clone(),
                 Rectangle::new(
                     DIAGRAM_MARGIN,
                     DIAGRAM_MARGIN,
-                    bbox.w + (TITLE_PADDING + TITLE_MARGIN) * 2,
-                    bbox.h + (TITLE_PADDING + TITLE_MARGIN) * 2,
+                    bbox.w + (ctx.theme.note_padding + TITLE_MARGIN) * 2,
+                    bbox.h + (ctx.theme.note_padding + TITLE_MARGIN) * 2,
                 ),
             ))
         }
     }
 
-    fn visit_signal(&self, _signal: &Signal<'i>, _ctx: &mut Self::Context) -> Self::Output {}
-
-    fn visit_participant(
-        &self,
-        _participant: &Participant<'i>,
-        _ctx: &mut Self::Context,
-    ) -> Self::Output {
+    fn visit_fragment(&self, fragment: &Fragment<'i>, ctx: &mut Self::Context) -> Self::Output {
+        for section in fragment.sections() {
+            for statement in section.statements() {
+                statement.accept(self, ctx)
+            }
+        }
     }
 
-    fn visit_note(&self, _note: &Note<'i>, _ctx: &mut Self::Context) -> Self::Output {}
+    fn visit_style(&self, style: &Style, ctx: &mut Self::Context) -> Self::Output {
+        // the last one wins, as with `theme`
+        ctx.style = Some(*style.kind());
+    }
 }
 
 #[derive(Debug)]
@@ -132,52 +323,58 @@ impl<'i> Visitor<'i> for LayoutCalculator {
     type Output = ();
     type Context = Layout<'i>;
 
+    fn default_action(&self, _ctx: &mut Self::Context) -> Self::Output {}
+
     fn visit_document(&self, document: &Document<'i>, ctx: &mut Self::Context) -> Self::Output {
         for statement in document {
             statement.accept(self, ctx)
         }
+
+        let y = ctx.signals.iter().map(|(_, r)| r.h).sum::<isize>();
+        for (_, _, _, y2) in &mut ctx.activations {
+            y2.get_or_insert(y);
+        }
     }
 
     fn visit_statement(&self, statement: &Statement<'i>, ctx: &mut Self::Context) -> Self::Output {
+        ctx.statements.push(statement.span());
         match statement {
             Statement::Title(title) => title.accept(self, ctx),
             Statement::Signal(signal) => signal.accept(self, ctx),
             Statement::Participant(participant) => participant.accept(self, ctx),
             Statement::Note(note) => note.accept(self, ctx),
+            Statement::Activate(activate) => activate.accept(self, ctx),
+            Statement::Deactivate(deactivate) => deactivate.accept(self, ctx),
+            Statement::Fragment(fragment) => fragment.accept(self, ctx),
+            Statement::Autonumber(autonumber) => autonumber.accept(self, ctx),
+            Statement::Style(style) => style.accept(self, ctx),
+            Statement::Theme(theme) => theme.accept(self, ctx),
         }
     }
 
-    fn visit_title(&self, _title: &Title<'i>, _ctx: &mut Self::Context) -> Self::Output {}
-
     fn visit_signal(&self, signal: &Signal<'i>, ctx: &mut Self::Context) -> Self::Output {
-        let mut add_actor = |actor: &Actor<'i>| {
-            if ctx.pos_by_actor(actor).is_none() {
-                let x = ctx.actors.iter().map(|(_, _, r)| r.w).sum::<isize>();
-                let bbox = text_bbox(actor.as_ref());
-                ctx.actors.push((
-                    actor.clone(),
-                    actor.clone(),
-                    Rectangle::new(
-                        bbox.x + x,
-                        bbox.y,
-                        bbox.w + (ACTOR_MARGIN + ACTOR_PADDING) * 2,
-                        bbox.h + (ACTOR_MARGIN + ACTOR_PADDING) * 2,
-                    ),
-                ))
-            }
-        };
+        ctx.add_actor(signal.from(), None);
+        ctx.add_actor(signal.to(), None);
 
-        add_actor(signal.from());
-        add_actor(signal.to());
-
-        let bbox = text_bbox(signal.message());
+        let number = ctx.numbering.as_mut().map(Numbering::next);
+        let label = match &number {
+            Some(number) => format!("{} {}", number, signal.message()),
+            None => signal.message().to_string(),
+        };
+        let label = wrap_text(&label, &ctx.theme).into_owned();
+        let bbox = text_bbox(&label, &ctx.theme);
         let y = ctx.signals.iter().map(|(_, r)| r.h).sum::<isize>();
-        let x1 = ctx.pos_by_actor(signal.from()).unwrap().center_x();
-        let x2 = ctx.pos_by_actor(signal.to()).unwrap().center_x();
+        let h = bbox.h + (SIGNAL_MARGIN + ctx.theme.padding) * 2;
         ctx.signals.push((
-            SignalKind::Signal(signal.clone()),
-            Rectangle::new(x1, y, x2, bbox.h + (SIGNAL_MARGIN + SIGNAL_PADDING) * 2),
+            SignalKind::Signal(signal.clone(), number, label),
+            Rectangle::new(0, y, bbox.w + (SIGNAL_MARGIN + ctx.theme.padding) * 2, h),
         ));
+
+        match signal.signal().activation() {
+            Some(Activation::Activate) => ctx.activate(signal.receiver(), y + h),
+            Some(Activation::Deactivate) => ctx.deactivate(signal.sender(), y + h),
+            None => {}
+        }
     }
 
     fn visit_participant(
@@ -185,28 +382,148 @@ impl<'i> Visitor<'i> for LayoutCalculator {
         participant: &Participant<'i>,
         ctx: &mut Self::Context,
     ) -> Self::Output {
-        let mut add_actor = |actor: &Actor<'i>, display_name: &Option<Actor<'i>>| {
-            if ctx.pos_by_actor(actor).is_none() {
-                let x = ctx.actors.iter().map(|(_, _, r)| r.w).sum::<isize>();
-                let display_name = display_name.as_ref().unwrap_or(actor).clone();
-                let bbox = text_bbox(display_name.as_ref());
-                ctx.actors.push((
-                    actor.clone(),
-                    display_name,
-                    Rectangle::new(
-                        bbox.x + x,
-                        bbox.y,
-                        bbox.w + (ACTOR_MARGIN + ACTOR_PADDING) * 2,
-                        bbox.h + (ACTOR_MARGIN + ACTOR_PADDING) * 2,
-                    ),
-                ))
+        ctx.add_actor(participant.actor(), participant.display_name().as_ref());
+    }
+
+    fn visit_note(&self, note: &Note<'i>, ctx: &mut Self::Context) -> Self::Output {
+        for actor in note.actors() {
+            ctx.add_actor(actor, None);
+        }
+
+        let message = wrap_text(note.message(), &ctx.theme).into_owned();
+        let bbox = text_bbox(&message, &ctx.theme);
+        let y = ctx.signals.iter().map(|(_, r)| r.h).sum::<isize>();
+        ctx.signals.push((
+            SignalKind::Note(note.clone(), message),
+            Rectangle::new(
+                0,
+                y,
+                bbox.w + (NOTE_MARGIN + ctx.theme.note_padding) * 2,
+                bbox.h + (NOTE_MARGIN + ctx.theme.note_padding) * 2,
+            ),
+        ));
+    }
+
+    fn visit_activate(&self, activate: &Activate<'i>, ctx: &mut Self::Context) -> Self::Output {
+        ctx.add_actor(activate.actor(), None);
+
+        let y = ctx.signals.iter().map(|(_, r)| r.h).sum::<isize>();
+        ctx.activate(activate.actor(), y);
+    }
+
+    fn visit_deactivate(
+        &self,
+        deactivate: &Deactivate<'i>,
+        ctx: &mut Self::Context,
+    ) -> Self::Output {
+        let y = ctx.signals.iter().map(|(_, r)| r.h).sum::<isize>();
+        ctx.deactivate(deactivate.actor(), y);
+    }
+
+    fn visit_fragment(&self, fragment: &Fragment<'i>, ctx: &mut Self::Context) -> Self::Output {
+        let index = ctx.fragments.len();
+        let depth = ctx
+            .signals
+            .iter()
+            .map(|(signal, _)| match signal {
+                SignalKind::Section(_, 0) => 1,
+                SignalKind::End(_) => -1,
+                _ => 0,
+            })
+            .sum::<isize>();
+        ctx.fragments.push((fragment.clone(), depth));
+        if let Some(numbering) = &mut ctx.numbering {
+            numbering.enter();
+        }
+
+        for (i, section) in fragment.sections().iter().enumerate() {
+            let guard = section.guard().map(|g| format!("[{}]", g));
+            let guard = text_bbox(&guard.unwrap_or_default(), &ctx.theme);
+            let mut w = guard.w + FRAGMENT_TAG_PADDING * 2;
+            if i == 0 {
+                let tag = text_bbox(fragment.kind().as_ref(), &ctx.theme);
+                w += tag.w + FRAGMENT_TAG_PADDING * 3;
+            }
+            let y = ctx.signals.iter().map(|(_, r)| r.h).sum::<isize>();
+            ctx.signals.push((
+                SignalKind::Section(index, i),
+                Rectangle::new(
+                    0,
+                    y,
+                    w,
+                    FRAGMENT_MARGIN + guard.h + FRAGMENT_TAG_PADDING * 2,
+                ),
+            ));
+
+            for statement in section.statements() {
+                statement.accept(self, ctx)
+            }
+        }
+
+        let y = ctx.signals.iter().map(|(_, r)| r.h).sum::<isize>();
+        ctx.signals.push((
+            SignalKind::End(index),
+            Rectangle::new(0, y, 0, FRAGMENT_MARGIN * 2),
+        ));
+        if let Some(numbering) = &mut ctx.numbering {
+            numbering.exit();
+        }
+    }
+
+    fn visit_autonumber(
+        &self,
+        autonumber: &Autonumber<'i>,
+        ctx: &mut Self::Context,
+    ) -> Self::Output {
+        ctx.numbering = match autonumber {
+            // a format without digits is reported by the parser
+            Autonumber::On(start, step, format, _) => {
+                let (start, step) = (start.unwrap_or(1), step.unwrap_or(1));
+                format
+                    .as_deref()
+                    .and_then(|format| Numbering::new(start, step, format))
+                    .or_else(|| Numbering::new(start, step, "1"))
             }
+            Autonumber::Off(_) => None,
         };
+    }
+}
+
+/// Applies the `theme` directives, in order, to the theme given as context.
+#[derive(Debug)]
+struct ApplyTheme;
+
+impl<'i> Visitor<'i> for ApplyTheme {
+    type Output = ();
+    type Context = Theme;
+
+    fn default_action(&self, _ctx: &mut Self::Context) -> Self::Output {}
 
-        add_actor(participant.actor(), participant.display_name());
+    fn visit_document(&self, document: &Document<'i>, ctx: &mut Self::Context) -> Self::Output {
+        for statement in document {
+            statement.accept(self, ctx)
+        }
     }
 
-    fn visit_note(&self, _note: &Note<'i>, _ctx: &mut Self::Context) -> Self::Output {}
+    fn visit_statement(&self, statement: &Statement<'i>, ctx: &mut Self::Context) -> Self::Output {
+        match statement {
+            Statement::Fragment(fragment) => fragment.accept(self, ctx),
+            Statement::Theme(theme) => theme.accept(self, ctx),
+            _ => {}
+        }
+    }
+
+    fn visit_fragment(&self, fragment: &Fragment<'i>, ctx: &mut Self::Context) -> Self::Output {
+        for section in fragment.sections() {
+            for statement in section.statements() {
+                statement.accept(self, ctx)
+            }
+        }
+    }
+
+    fn visit_theme(&self, theme: &ThemeDirective<'i>, ctx: &mut Self::Context) -> Self::Output {
+        ctx.apply(theme)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -227,10 +544,118 @@ impl Rectangle {
     }
 }
 
+/// Widen `gaps` until each `(first gap, last gap + 1, width)` of `spans`
+/// fits, spreading what is missing evenly over the gaps a span crosses.
+fn spread_gaps(gaps: &mut [isize], mut spans: Vec<(usize, usize, isize)>) {
+    // narrow spans first, so that wide ones only make up the remainder
+    spans.sort_by_key(|(a, b, _)| b - a);
+    for (a, b, width) in spans {
+        let gaps = &mut gaps[a..b];
+        let shortage = width - gaps.iter().sum::<isize>();
+        if shortage > 0 {
+            let n = gaps.len() as isize;
+            for (i, gap) in gaps.iter_mut().enumerate() {
+                *gap += shortage / n + if (i as isize) < shortage % n { 1 } else { 0 };
+            }
+        }
+    }
+}
+
+/// Message numbers handed out after `autonumber`.
+#[derive(Debug)]
+struct Numbering {
+    /// numbers of the enclosing fragments, for nested numbering only
+    parents: Vec<usize>,
+    /// next number at each fragment level
+    counters: Vec<usize>,
+    step: usize,
+    nested: bool,
+    width: usize,
+    prefix: String,
+    suffix: String,
+}
+
+impl Numbering {
+    /// `format` is an example of the first number: `1.`, `[01]` or `1.1`.
+    /// Its first run of digits is replaced by the message number, zero
+    /// padded when it starts with `0`; dots inside it turn on nested
+    /// numbering inside fragments. `None` if there are no digits.
+    fn new(start: usize, step: usize, format: &str) -> Option<Self> {
+        let begin = format.find(|c: char| c.is_ascii_digit())?;
+        let end = format[begin..]
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .map(|end| begin + end)
+            .unwrap_or(format.len());
+        let example = format[begin..end].trim_end_matches('.');
+        let end = begin + example.len();
+        let first = example.split('.').next().unwrap_or_default();
+        Some(Self {
+            parents: vec![],
+            counters: vec![start],
+            step,
+            nested: example.contains('.'),
+            width: if first.starts_with('0') {
+                first.len()
+            } else {
+                1
+            },
+            prefix: format[..begin].to_string(),
+            suffix: format[end..].to_string(),
+        })
+    }
+
+    fn next(&mut self) -> String {
+        let step = if self.counters.len() > 1 {
+            1
+        } else {
+            self.step
+        };
+        let counter = self.counters.last_mut().unwrap();
+        let number = *counter;
+        *counter = counter.saturating_add(step);
+
+        let number = self
+            .parents
+            .iter()
+            .chain(Some(&number))
+            .map(|n| format!("{:0width$}", n, width = self.width))
+            .collect::<Vec<_>>()
+            .join(".");
+        format!("{}{}{}", self.prefix, number, self.suffix)
+    }
+
+    fn enter(&mut self) {
+        if self.nested {
+            let step = if self.counters.len() > 1 {
+                1
+            } else {
+                self.step
+            };
+            let counter = self.counters.last_mut().unwrap();
+            self.parents.push(*counter);
+            *counter = counter.saturating_add(step);
+            self.counters.push(1);
+        }
+    }
+
+    fn exit(&mut self) {
+        if self.counters.len() > 1 {
+            self.parents.pop();
+            self.counters.pop();
+        }
+    }
+}
+
 #[derive(Debug)]
 enum SignalKind<'i> {
-    Signal(Signal<'i>),
-    //Note(Note<'i>),
+    /// a signal with its number, if it has one, and its label, numbered and
+    /// wrapped if need be
+    Signal(Signal<'i>, Option<String>, String),
+    /// a note with its message, wrapped if need be
+    Note(Note<'i>, String),
+    /// Top of the `n`th section of a fragment: the header or an `else`.
+    Section(usize, usize),
+    End(usize),
 }
 
 #[derive(Debug, Default)]
@@ -238,11 +663,311 @@ struct Layout<'i> {
     title: Option<(Title<'i>, Rectangle)>,
     actors: Vec<(Actor<'i>, Actor<'i>, Rectangle)>,
     signals: Vec<(SignalKind<'i>, Rectangle)>,
+    /// (actor, nesting level, top, bottom) of each activation bar
+    activations: Vec<(Actor<'i>, isize, isize, Option<isize>)>,
+    /// fragments with their nesting depth
+    fragments: Vec<(Fragment<'i>, isize)>,
+    numbering: Option<Numbering>,
+    /// set by the last `style` directive, which wins over the render options
+    style: Option<StyleKind>,
+    /// spans of all statements, nested ones included, in document order
+    statements: Vec<Span>,
+    theme: Theme,
     width: isize,
     height: isize,
 }
 
 impl<'i> Layout<'i> {
+    fn activate(&mut self, actor: &Actor<'i>, y: isize) {
+        let level = self
+            .activations
+            .iter()
+            .filter(|(a, _, _, y2)| a == actor && y2.is_none())
+            .count() as isize;
+        self.activations.push((actor.clone(), level, y, None));
+    }
+
+    fn deactivate(&mut self, actor: &Actor<'i>, y: isize) {
+        if let Some((_, _, _, y2)) = self
+            .activations
+            .iter_mut()
+            .rev()
+            .find(|(a, _, _, y2)| a == actor && y2.is_none())
+        {
+            *y2 = Some(y);
+        }
+    }
+
+    /// Where a signal at height `y` attaches to the lifeline of `actor`:
+    /// the edge of the most nested activation bar facing the other end, or
+    /// the centre line when the actor is not active.
+    fn lifeline_x(&self, actor: &Actor<'_>, y: isize, right: bool) -> isize {
+        let x = self.pos_by_actor(actor).unwrap().center_x();
+        let level = self
+            .activations
+            .iter()
+            .filter(|(a, _, y1, y2)| a == actor && *y1 <= y && y <= y2.unwrap_or(isize::MAX))
+            .map(|(_, level, _, _)| *level)
+            .max();
+        match level {
+            None => x,
+            // each level is drawn half a bar to the right of the one below
+            Some(level) => {
+                let left = x - ACTIVATION_WIDTH / 2 + level * ACTIVATION_WIDTH / 2;
+                if right {
+                    left + ACTIVATION_WIDTH
+                } else {
+                    left
+                }
+            }
+        }
+    }
+
+    fn add_actor(&mut self, actor: &Actor<'i>, display_name: Option<&Actor<'i>>) {
+        if self.pos_by_actor(actor).is_none() {
+            let display_name = display_name.unwrap_or(actor);
+            let bbox = text_bbox(&self.wrap(display_name.as_ref()), &self.theme);
+            self.actors.push((
+                actor.clone(),
+                display_name.clone(),
+                Rectangle::new(
+                    0,
+                    bbox.y,
+                    bbox.w + (ACTOR_MARGIN + self.theme.padding) * 2,
+                    bbox.h + (ACTOR_MARGIN + self.theme.padding) * 2,
+                ),
+            ))
+        }
+    }
+
+    /// `text` as drawn, wrapped at the theme's `max_text_width`.
+    fn wrap<'t>(&self, text: &'t str) -> Cow<'t, str> {
+        wrap_text(text, &self.theme)
+    }
+
+    /// The name shown for `target`, as written.
+    fn display_name<'a>(&'a self, target: &'a Actor<'_>) -> &'a str {
+        self.actors
+            .iter()
+            .find(|(alias, _, _)| alias == target)
+            .map(|(_, display_name, _)| display_name.as_ref())
+            .unwrap_or_else(|| target.as_ref())
+    }
+
+    fn index_by_actor(&self, target: &Actor<'_>) -> Option<usize> {
+        self.actors.iter().position(|(alias, _, _)| alias == target)
+    }
+
+    /// Place actors from left to right.
+    ///
+    /// `gaps[0]` is the distance from the left edge to the first lifeline,
+    /// `gaps[i]` the distance between the lifelines of actor `i - 1` and `i`
+    /// and the last one the distance from the last lifeline to the right edge.
+    /// Every signal label and note widens the gaps it crosses; a label that
+    /// crosses several gaps is spread evenly over them.
+    fn place_actors(&mut self) {
+        let len = self.actors.len();
+        let half_width = |i: usize| -> isize {
+            let w = self.actors[i].2.w;
+            w - w / 2
+        };
+
+        let mut gaps = (0..=len)
+            .map(|i| match i {
+                0 if len == 0 => 0,
+                0 => self.actors[0].2.w / 2,
+                i if i == len => half_width(len - 1),
+                i => half_width(i - 1) + self.actors[i].2.w / 2,
+            })
+            .collect::<Vec<_>>();
+
+        // (first gap, last gap + 1, width)
+        let mut spans = vec![];
+        for (signal, rectangle) in &self.signals {
+            match signal {
+                SignalKind::Signal(signal, ..) => {
+                    let a = self.index_by_actor(signal.from()).unwrap() + 1;
+                    let b = self.index_by_actor(signal.to()).unwrap() + 1;
+                    if a == b {
+                        spans.push((a, a + 1, rectangle.w + SELF_SIGNAL_WIDTH));
+                    } else {
+                        spans.push((usize::min(a, b), usize::max(a, b), rectangle.w));
+                    }
+                }
+                SignalKind::Note(note, _) => {
+                    let mut indices = note
+                        .actors()
+                        .into_iter()
+                        .map(|actor| self.index_by_actor(actor).unwrap() + 1)
+                        .collect::<Vec<_>>();
+                    indices.sort_unstable();
+                    let a = indices[0];
+                    let b = indices[indices.len() - 1];
+                    match note {
+                        Note::LeftOf(..) => spans.push((a - 1, a, rectangle.w - NOTE_OVERLAP)),
+                        Note::RightOf(..) => spans.push((a, a + 1, rectangle.w - NOTE_OVERLAP)),
+                        Note::Over(..) if a == b => {
+                            spans.push((a - 1, a, rectangle.w / 2));
+                            spans.push((a, a + 1, rectangle.w - rectangle.w / 2));
+                        }
+                        Note::Over(..) => {
+                            spans.push((a, b, rectangle.w - (NOTE_MARGIN + NOTE_OVERLAP) * 2))
+                        }
+                    }
+                }
+                SignalKind::Section(index, 0) => {
+                    let (a, b) = match self.fragment_actors(*index) {
+                        Some((a, b)) => (a + 1, b + 1),
+                        None if len > 0 => (1, len),
+                        None => continue,
+                    };
+                    let padding = self.fragment_padding(*index);
+                    let header = self
+                        .fragment_rows(*index)
+                        .iter()
+                        .filter(
+                            |(signal, _)| matches!(signal, SignalKind::Section(i, _) if i == index),
+                        )
+                        .map(|(_, r)| r.w)
+                        .max()
+                        .unwrap_or(0);
+                    spans.push((a - 1, a, padding + FRAGMENT_MARGIN));
+                    spans.push((b, b + 1, padding + FRAGMENT_MARGIN));
+                    spans.push((a, b + 1, header - padding));
+                }
+                SignalKind::Section(..) | SignalKind::End(_) => {}
+            }
+        }
+
+        spread_gaps(&mut gaps, spans);
+
+        let mut x = 0;
+        for (i, gap) in gaps.iter().enumerate().take(len) {
+            x += gap;
+            let rectangle = &mut self.actors[i].2;
+            rectangle.x = x - rectangle.w / 2;
+        }
+
+        self.width = isize::max(
+            x + gaps[len],
+            self.title
+                .as_ref()
+                .map(|(_, r)| r.x + r.w + DIAGRAM_MARGIN)
+                .unwrap_or(0),
+        );
+    }
+
+    /// Rows from the header of a fragment to its end, both inclusive.
+    fn fragment_rows(&self, index: usize) -> &[(SignalKind<'i>, Rectangle)] {
+        let first = self
+            .signals
+            .iter()
+            .position(|(signal, _)| matches!(signal, SignalKind::Section(i, 0) if *i == index))
+            .unwrap();
+        let last = self
+            .signals
+            .iter()
+            .position(|(signal, _)| matches!(signal, SignalKind::End(i) if *i == index))
+            .unwrap();
+        &self.signals[first..=last]
+    }
+
+    /// Leftmost and rightmost actors taking part in a fragment.
+    fn fragment_actors(&self, index: usize) -> Option<(usize, usize)> {
+        let indices = self
+            .fragment_rows(index)
+            .iter()
+            .flat_map(|(signal, _)| match signal {
+                SignalKind::Signal(signal, ..) => vec![signal.from(), signal.to()],
+                SignalKind::Note(note, _) => note.actors(),
+                _ => vec![],
+            })
+            .map(|actor| self.index_by_actor(actor).unwrap())
+            .collect::<Vec<_>>();
+        Some((*indices.iter().min()?, *indices.iter().max()?))
+    }
+
+    /// Distance between a fragment's frame and its contents, growing with
+    /// the depth of the fragments nested inside so that they are inset.
+    fn fragment_padding(&self, index: usize) -> isize {
+        let depth = self.fragments[index].1;
+        let inner = self
+            .fragment_rows(index)
+            .iter()
+            .filter_map(|(signal, _)| match signal {
+                SignalKind::End(i) => Some(self.fragments[*i].1),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(depth);
+        FRAGMENT_PADDING * (1 + inner - depth)
+    }
+
+    /// Horizontal position and width of the frame drawn for a fragment.
+    fn fragment_x(&self, index: usize) -> (isize, isize) {
+        let padding = self.fragment_padding(index);
+        let mut left = isize::MAX;
+        let mut right = isize::MIN;
+        let mut header = 0;
+        for (signal, rectangle) in self.fragment_rows(index) {
+            match signal {
+                SignalKind::Signal(signal, ..) => {
+                    for actor in &[signal.from(), signal.to()] {
+                        let x = self.pos_by_actor(actor).unwrap().center_x();
+                        left = isize::min(left, x - padding);
+                        right = isize::max(right, x + padding);
+                    }
+                    if signal.from() == signal.to() {
+                        let x = self.pos_by_actor(signal.from()).unwrap().center_x();
+                        right = isize::max(right, x + SELF_SIGNAL_WIDTH + rectangle.w);
+                    }
+                }
+                SignalKind::Note(note, _) => {
+                    let (x, w) = self.note_x(note, rectangle);
+                    left = isize::min(left, x + NOTE_MARGIN - padding);
+                    right = isize::max(right, x + w - NOTE_MARGIN + padding);
+                }
+                SignalKind::Section(i, _) if *i == index => {
+                    header = isize::max(header, rectangle.w)
+                }
+                SignalKind::Section(..) | SignalKind::End(_) => {}
+            }
+        }
+
+        if left > right {
+            let centers = self.actors.iter().map(|(_, _, r)| r.center_x());
+            left = centers.clone().min().unwrap_or(padding) - padding;
+            right = centers.max().unwrap_or(padding) + padding;
+        }
+        (left, isize::max(right - left, header))
+    }
+
+    /// Horizontal position and width of the box drawn for a note.
+    fn note_x(&self, note: &Note<'_>, rectangle: &Rectangle) -> (isize, isize) {
+        match note {
+            Note::LeftOf(actor, ..) => {
+                let x = self.pos_by_actor(actor).unwrap().center_x();
+                (x - rectangle.w + NOTE_OVERLAP, rectangle.w)
+            }
+            Note::RightOf(actor, ..) => {
+                let x = self.pos_by_actor(actor).unwrap().center_x();
+                (x - NOTE_OVERLAP, rectangle.w)
+            }
+            Note::Over(actor, None, ..) => {
+                let x = self.pos_by_actor(actor).unwrap().center_x();
+                (x - rectangle.w / 2, rectangle.w)
+            }
+            Note::Over(actor, Some(actor2), ..) => {
+                let x1 = self.pos_by_actor(actor).unwrap().center_x();
+                let x2 = self.pos_by_actor(actor2).unwrap().center_x();
+                let (x1, x2) = (isize::min(x1, x2), isize::max(x1, x2));
+                let overlap = NOTE_MARGIN + NOTE_OVERLAP;
+                let w = isize::max(x2 - x1 + overlap * 2, rectangle.w);
+                ((x1 + x2) / 2 - w / 2, w)
+            }
+        }
+    }
+
     fn pos_by_actor(&self, target: &Actor<'_>) -> Option<Rectangle> {
         for (alias, _, rectangle) in &self.actors {
             if alias == target {
@@ -259,7 +984,7 @@ struct Wobble {
 }
 
 impl Wobble {
-    fn wobble(&mut self, x1: isize, y1: isize, x2: isize, y2: isize) -> String {
+    fn wobble(&mut self, x1: isize, y1: isize, x2: isize, y2: isize) -> Segment {
         let factor = (((x2 - x1).pow(2) + (y2 - y1).pow(2)) as f32).sqrt() / 25.0;
         self.hasher.write_u32(factor.to_bits());
 
@@ -268,13 +993,13 @@ impl Wobble {
         let r2 = (((self.hasher.finish() % 60) as f32) / 100.0) + 0.2;
         self.hasher.write_u32(r2.to_bits());
 
-        let xfactor = if self.hasher.finish() % 2 == 0 {
+        let xfactor = if self.hasher.finish().is_multiple_of(2) {
             factor
         } else {
             -factor
         };
         self.hasher.write_u32(xfactor.to_bits());
-        let yfactor = if self.hasher.finish() % 2 == 0 {
+        let yfactor = if self.hasher.finish().is_multiple_of(2) {
             factor
         } else {
             -factor
@@ -287,66 +1012,217 @@ impl Wobble {
         let p2x = ((x2 - x1) as f32) * r2 + (x1 as f32) - xfactor;
         let p2y = ((y2 - y1) as f32) * r2 + (y1 as f32) - yfactor;
 
-        format!("C{:.1},{:.1} {:.1},{:.1} {},{}", p1x, p1y, p2x, p2y, x2, y2)
+        // a tenth of a pixel is close enough, and keeps the SVG short
+        let round = |v: f32| (v * 10.0).round() / 10.0;
+        Segment::CubicTo(
+            Point::new(round(p1x), round(p1y)),
+            Point::new(round(p2x), round(p2y)),
+            point(x2, y2),
+        )
+    }
+}
+
+fn point(x: isize, y: isize) -> Point {
+    Point::new(x as f32, y as f32)
+}
+
+/// `names` as in a sentence: `A`, `A and B` or `A, B and C`.
+fn list(names: &[&str]) -> String {
+    match names {
+        [] => String::new(),
+        [name] => name.to_string(),
+        [rest @ .., last] => format!("{} and {}", rest.join(", "), last),
     }
 }
 
 #[derive(Debug)]
-struct SequenceDiagram<'i> {
-    document: Document<'i>,
+struct SequenceDiagram<'d, 'i> {
+    document: &'d Document<'i>,
+    style: StyleKind,
+    theme: Theme,
 }
 
-impl<'i> SequenceDiagram<'i> {
-    fn parse(text: &'i str) -> Result<Self, ParseError> {
-        let document = parse::parse(text)?;
-        Ok(Self { document })
+impl<'d, 'i> SequenceDiagram<'d, 'i> {
+    fn new(document: &'d Document<'i>, options: &RenderOptions) -> Self {
+        let mut theme = options.theme.clone();
+        document.accept(&ApplyTheme, &mut theme);
+        Self {
+            document,
+            style: options.style,
+            theme,
+        }
     }
 
-    fn draw(&self) -> String {
+    fn draw(&mut self) -> Paper {
         let layout = self.layout();
-
-        let title_height =
-            layout.title.as_ref().map(|(_, r)| r.h).unwrap_or_else(|| 0) + DIAGRAM_MARGIN;
-        let signal_height = layout.signals.iter().map(|(_, r)| r.h).sum::<isize>();
-        let actor_height = layout.actors.iter().map(|(_, _, r)| r.h).max().unwrap_or(0);
+        if let Some(style) = layout.style {
+            self.style = style;
+        }
 
         let mut paper = Paper::builder()
-            .w(layout
-                .actors
-                .iter()
-                .map(|(_, _, r)| r.x + r.w)
-                .max()
-                .unwrap_or(0))
-            .h(title_height + signal_height + (actor_height * 2))
+            .width(layout.width as f32)
+            .height(layout.height as f32)
+            .theme(self.theme.clone())
             .build();
         let mut w = Wobble::default();
 
+        // in reading order, each fragment just before its first row
         self.draw_title(&mut paper, &layout, &mut w);
         self.draw_actor(&mut paper, &layout, &mut w);
         self.draw_signals(&mut paper, &layout, &mut w);
 
-        paper.to_svg_string()
+        paper.title = layout
+            .title
+            .as_ref()
+            .map(|(title, _)| title.as_ref().to_string());
+        paper.description = self.describe(&layout);
+        paper
+    }
+
+    /// What the diagram shows, in plain sentences: who takes part, then
+    /// every row from top to bottom.
+    fn describe(&self, layout: &Layout) -> String {
+        let mut sentences = vec![];
+        if !layout.actors.is_empty() {
+            let names = layout
+                .actors
+                .iter()
+                .map(|(_, display_name, _)| display_name.as_ref())
+                .collect::<Vec<_>>();
+            sentences.push(format!("Sequence diagram between {}.", list(&names)));
+        }
+        for (row, _) in &layout.signals {
+            let label = self.row_label(layout, row);
+            let mut chars = label.chars();
+            if let Some(first) = chars.next() {
+                sentences.push(format!("{}{}.", first.to_uppercase(), chars.as_str()));
+            }
+        }
+        sentences.join(" ")
+    }
+
+    /// What a row shows, in words.
+    fn row_label(&self, layout: &Layout, row: &SignalKind<'_>) -> String {
+        match row {
+            SignalKind::Signal(signal, number, _) => {
+                // as written rather than as wrapped
+                let label = match number {
+                    Some(number) => format!("{} {}", number, signal.message()),
+                    None => signal.message().to_string(),
+                };
+                let arrow = signal.signal();
+                if signal.from() == signal.to() {
+                    let from = layout.display_name(signal.from());
+                    format!("{} sends '{}' to itself", from, label)
+                } else if arrow.start_arrow_type() != ArrowType::None
+                    && arrow.arrow_type() != ArrowType::None
+                {
+                    let from = layout.display_name(signal.from());
+                    let to = layout.display_name(signal.to());
+                    format!("{} and {} exchange '{}'", from, to, label)
+                } else {
+                    let from = layout.display_name(signal.sender());
+                    let to = layout.display_name(signal.receiver());
+                    format!("{} sends '{}' to {}", from, label, to)
+                }
+            }
+            SignalKind::Note(note, _) => {
+                let names = note
+                    .actors()
+                    .into_iter()
+                    .map(|actor| layout.display_name(actor))
+                    .collect::<Vec<_>>();
+                let placement = match note {
+                    Note::LeftOf(..) => "left of",
+                    Note::RightOf(..) => "right of",
+                    Note::Over(..) => "over",
+                };
+                format!("Note {} {}: {}", placement, list(&names), note.message())
+            }
+            SignalKind::Section(index, section) => {
+                let fragment = &layout.fragments[*index].0;
+                let start = if *section == 0 {
+                    fragment.kind().as_ref()
+                } else {
+                    "else"
+                };
+                match fragment.sections()[*section].guard() {
+                    Some(guard) => format!("{} {}", start, guard),
+                    None => start.to_string(),
+                }
+            }
+            SignalKind::End(index) => {
+                format!("end of {}", layout.fragments[*index].0.kind().as_ref())
+            }
+        }
     }
 
-    fn layout(&self) -> Layout {
-        let mut layout = Layout::default();
+    fn layout(&self) -> Layout<'i> {
+        let mut layout = Layout {
+            theme: self.theme.clone(),
+            ..Layout::default()
+        };
 
         self.document.accept(&LayoutTitle, &mut layout);
         self.document.accept(&LayoutCalculator, &mut layout);
+        layout.place_actors();
+
+        let title_height =
+            layout.title.as_ref().map(|(_, r)| r.h).unwrap_or_else(|| 0) + DIAGRAM_MARGIN;
+        let signal_height = layout.signals.iter().map(|(_, r)| r.h).sum::<isize>();
+        let actor_height = layout.actors.iter().map(|(_, _, r)| r.h).max().unwrap_or(0);
+        layout.height = title_height + signal_height + (actor_height * 2);
 
         layout
     }
 
+    fn stroke(&self) -> Stroke {
+        Stroke::new(self.theme.line_color.as_str(), self.theme.line_width as f32)
+    }
+
+    fn text(&self, x: isize, y: isize, text: &str) -> Text {
+        Text::new(x as f32, y as f32, text, TextStyle::from_theme(&self.theme))
+    }
+
+    /// A rectangle filled with the theme's `fill`, and not outlined.
+    fn fill(&self, rectangle: &Rectangle) -> Rect {
+        let Rectangle { x, y, w, h } = *rectangle;
+        Rect::new(x as f32, y as f32, w as f32, h as f32)
+            .with_fill(Fill::new(self.theme.fill.as_str()))
+    }
+
+    /// A group of the class `class` for what is drawn for the statement at
+    /// `span`, with the index of the statement and its line.
+    fn statement_group(&self, class: &str, layout: &Layout, span: Span) -> Group {
+        let group = Group::new().with_class(class);
+        // statements built by hand all have the default span
+        if span == Span::default() {
+            return group;
+        }
+        match layout.statements.iter().position(|s| *s == span) {
+            Some(index) => group
+                .with_id(format!("statement-{}", index))
+                .with_data("index", index)
+                .with_data("line", span.start_line_col().0),
+            None => group,
+        }
+    }
+
     fn draw_title(&self, paper: &mut Paper, layout: &Layout, w: &mut Wobble) {
         if let Some((title, rectangle)) = &layout.title {
+            let mut group = self
+                .statement_group("title", layout, title.span())
+                .with_label(format!("Title: {}", title.as_ref()));
             self.draw_text_box(
-                paper,
+                &mut group,
                 rectangle,
                 title.as_ref(),
                 TITLE_MARGIN,
-                TITLE_PADDING,
+                self.theme.note_padding,
+                TextAnchor::Middle,
                 w,
             );
+            paper.push(group);
         }
     }
 
@@ -354,128 +1230,294 @@ impl<'i> SequenceDiagram<'i> {
         let y = layout.title.as_ref().map(|(_, r)| r.h).unwrap_or_else(|| 0) + DIAGRAM_MARGIN;
         let signal_height = layout.signals.iter().map(|(_, r)| r.h).sum::<isize>();
 
-        for (_, actor, rectangle) in &layout.actors {
+        let y2 = layout.actors.iter().map(|(_, _, r)| r.h).max().unwrap_or(0);
+
+        for (i, (alias, actor, rectangle)) in layout.actors.iter().enumerate() {
+            let mut group = Group::new()
+                .with_class("actor")
+                .with_id(format!("actor-{}", i))
+                .with_data("actor", alias.as_ref())
+                .with_label(format!("Participant {}", actor.as_ref()));
+            // as high as the highest, so that all lifelines start and end together
             let mut rectangle = rectangle.clone();
+            rectangle.h = y2;
             rectangle.y = y;
+            let name = layout.wrap(actor.as_ref());
+            let mut head = Group::new().with_class("actor-box");
             self.draw_text_box(
-                paper,
+                &mut head,
                 &rectangle,
-                &actor.as_ref(),
+                &name,
                 ACTOR_MARGIN,
-                ACTOR_PADDING,
+                self.theme.padding,
+                TextAnchor::Middle,
                 w,
             );
+            group.push(head);
 
             rectangle.y = y + rectangle.h + signal_height;
+            let mut foot = Group::new().with_class("actor-box");
             self.draw_text_box(
-                paper,
+                &mut foot,
                 &rectangle,
-                &actor.as_ref(),
+                &name,
                 ACTOR_MARGIN,
-                ACTOR_PADDING,
+                self.theme.padding,
+                TextAnchor::Middle,
                 w,
             );
+            group.push(foot);
 
+            let mut lifeline = Group::new().with_class("lifeline");
             self.draw_line(
-                paper,
+                &mut lifeline,
                 rectangle.center_x(),
                 y + rectangle.h - ACTOR_MARGIN,
                 rectangle.center_x(),
                 y + rectangle.h + ACTOR_MARGIN + signal_height,
                 None,
+                None,
                 false,
                 w,
             );
+            group.push(lifeline);
+
+            for (_, level, top, bottom) in layout.activations.iter().filter(|(a, ..)| a == alias) {
+                let bar = Rectangle::new(
+                    rectangle.center_x() - ACTIVATION_WIDTH / 2 + level * ACTIVATION_WIDTH / 2,
+                    y + y2 + top,
+                    ACTIVATION_WIDTH,
+                    bottom.unwrap_or(signal_height) - top,
+                );
+                let mut activation = Group::new().with_class("activation");
+                activation.push(self.fill(&bar));
+                self.draw_rect(&mut activation, &bar, w);
+                group.push(activation);
+            }
+            paper.push(group);
+        }
+    }
+
+    fn draw_fragment(&self, layout: &Layout, index: usize, w: &mut Wobble) -> Group {
+        let y = layout.title.as_ref().map(|(_, r)| r.h).unwrap_or_else(|| 0) + DIAGRAM_MARGIN;
+        let y2 = layout.actors.iter().map(|(_, _, r)| r.h).max().unwrap_or(0);
+
+        let fragment = &layout.fragments[index].0;
+        let rows = layout.fragment_rows(index);
+        let label = rows
+            .iter()
+            .filter(|(row, _)| matches!(row, SignalKind::Section(i, _) if *i == index))
+            .map(|(row, _)| self.row_label(layout, row))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let mut group = self
+            .statement_group("fragment", layout, fragment.span())
+            .with_data("kind", fragment.kind().as_ref())
+            .with_label(label);
+        let rows = layout.fragment_rows(index);
+        let (x, width) = layout.fragment_x(index);
+        let top = rows[0].1.y + y + y2 + FRAGMENT_MARGIN;
+        let bottom = rows[rows.len() - 1].1.y + y + y2 + FRAGMENT_MARGIN;
+        self.draw_rect(&mut group, &Rectangle::new(x, top, width, bottom - top), w);
+
+        let tag = text_bbox(fragment.kind().as_ref(), &self.theme);
+        let tw = tag.w + FRAGMENT_TAG_PADDING * 2;
+        let th = tag.h + FRAGMENT_TAG_PADDING * 2;
+        let notch = FRAGMENT_TAG_PADDING;
+        group.push(Path::new(
+            vec![
+                Segment::MoveTo(point(x, top + th)),
+                self.segment(x, top + th, x + tw, top + th, w),
+                self.segment(x + tw, top + th, x + tw + notch, top + th - notch, w),
+                self.segment(x + tw + notch, top + th - notch, x + tw + notch, top, w),
+            ],
+            self.stroke(),
+        ));
+        group.push(self.text(
+            x + FRAGMENT_TAG_PADDING,
+            top + FRAGMENT_TAG_PADDING,
+            fragment.kind().as_ref(),
+        ));
+
+        for (signal, rectangle) in rows {
+            let (i, section) = match signal {
+                SignalKind::Section(i, section) if *i == index => (*i, *section),
+                _ => continue,
+            };
+            let guard = match layout.fragments[i].0.sections()[section].guard() {
+                Some(guard) => format!("[{}]", guard),
+                None => String::new(),
+            };
+            let line_y = rectangle.y + y + y2 + FRAGMENT_MARGIN;
+            if section == 0 {
+                group.push(self.text(
+                    x + tw + notch + FRAGMENT_TAG_PADDING,
+                    line_y + FRAGMENT_TAG_PADDING,
+                    &guard,
+                ));
+            } else {
+                self.draw_line(
+                    &mut group,
+                    x,
+                    line_y,
+                    x + width,
+                    line_y,
+                    None,
+                    None,
+                    true,
+                    w,
+                );
+                group.push(self.text(
+                    x + FRAGMENT_TAG_PADDING,
+                    line_y + FRAGMENT_TAG_PADDING,
+                    &guard,
+                ));
+            }
         }
+        group
     }
 
     fn draw_signals(&self, paper: &mut Paper, layout: &Layout, w: &mut Wobble) {
         let y = layout.title.as_ref().map(|(_, r)| r.h).unwrap_or_else(|| 0) + DIAGRAM_MARGIN;
         let y2 = layout.actors.iter().map(|(_, _, r)| r.h).max().unwrap_or(0);
 
-        for (signal, rectangle) in &layout.signals {
-            match signal {
-                SignalKind::Signal(signal) => {
+        for (row, rectangle) in &layout.signals {
+            paper.push_break((rectangle.y + y + y2 + ROW_BREAK_OFFSET) as f32);
+            let group = match row {
+                SignalKind::Signal(signal, _, label) => {
+                    let mut group = self
+                        .statement_group("signal", layout, signal.span())
+                        .with_data("from", signal.from().as_ref())
+                        .with_data("to", signal.to().as_ref())
+                        .with_label(self.row_label(layout, row));
+                    let mut label_group = Group::new().with_class("signal-label");
+                    let right = layout.pos_by_actor(signal.from()).unwrap().center_x()
+                        <= layout.pos_by_actor(signal.to()).unwrap().center_x();
+                    let arrow_y = rectangle.y + rectangle.h;
+                    let x1 = layout.lifeline_x(signal.from(), arrow_y, right);
+                    let x2 = layout.lifeline_x(signal.to(), arrow_y, !right);
+                    let marker = |arrow_type| match arrow_type {
+                        ArrowType::Normal => Some(Marker::ArrowBlock),
+                        ArrowType::Open => Some(Marker::ArrowOpen),
+                        ArrowType::None => None,
+                    };
+                    let marker_start = marker(signal.signal().start_arrow_type());
+                    let marker_end = marker(signal.signal().arrow_type());
+                    let dash = signal.signal().line_type() == LineType::Dot;
                     if signal.from() == signal.to() {
+                        let x1 =
+                            layout.lifeline_x(signal.from(), rectangle.y + SIGNAL_MARGIN, true);
+                        let x2 = layout.lifeline_x(signal.to(), arrow_y, true);
                         self.draw_text(
-                            paper,
-                            signal.message(),
-                            rectangle.x + SELF_SIGNAL_WIDTH,
+                            &mut label_group,
+                            label,
+                            x1 + SELF_SIGNAL_WIDTH,
                             rectangle.y + y + y2,
                             SIGNAL_MARGIN,
-                            SIGNAL_PADDING,
+                            self.theme.padding,
                             true,
                         );
+                        group.push(label_group);
 
                         self.draw_line(
-                            paper,
-                            rectangle.x,
+                            &mut group,
+                            x1,
                             rectangle.y + y + y2 + SIGNAL_MARGIN,
-                            rectangle.x + SELF_SIGNAL_WIDTH,
+                            x1 + SELF_SIGNAL_WIDTH,
                             rectangle.y + y + y2 + SIGNAL_MARGIN,
+                            marker_start,
                             None,
-                            signal.signal().line_type() == LineType::Dot,
+                            dash,
                             w,
                         );
                         self.draw_line(
-                            paper,
-                            rectangle.x + SELF_SIGNAL_WIDTH,
+                            &mut group,
+                            x1 + SELF_SIGNAL_WIDTH,
                             rectangle.y + y + y2 + SIGNAL_MARGIN,
-                            rectangle.x + SELF_SIGNAL_WIDTH,
+                            x1 + SELF_SIGNAL_WIDTH,
                             rectangle.y + y + y2 + rectangle.h,
                             None,
-                            signal.signal().line_type() == LineType::Dot,
+                            None,
+                            dash,
                             w,
                         );
                         self.draw_line(
-                            paper,
-                            rectangle.x + SELF_SIGNAL_WIDTH,
+                            &mut group,
+                            x1 + SELF_SIGNAL_WIDTH,
                             rectangle.y + y + y2 + rectangle.h,
-                            rectangle.x,
+                            x2,
                             rectangle.y + y + y2 + rectangle.h,
-                            match signal.signal().arrow_type() {
-                                ArrowType::Normal => Some(MarkerEnd::ArrowBlock),
-                                ArrowType::Open => Some(MarkerEnd::ArrowOpen),
-                                ArrowType::None => None,
-                            },
-                            signal.signal().line_type() == LineType::Dot,
+                            None,
+                            marker_end,
+                            dash,
                             w,
                         );
                     } else {
                         self.draw_text(
-                            paper,
-                            signal.message(),
-                            rectangle.x,
+                            &mut label_group,
+                            label,
+                            x1,
                             rectangle.y + y + y2,
                             SIGNAL_MARGIN,
-                            SIGNAL_PADDING,
-                            rectangle.x < rectangle.w,
+                            self.theme.padding,
+                            x1 < x2,
                         );
+                        group.push(label_group);
 
                         self.draw_line(
-                            paper,
-                            rectangle.x,
+                            &mut group,
+                            x1,
                             rectangle.y + y + y2 + rectangle.h,
-                            rectangle.w,
+                            x2,
                             rectangle.y + y + y2 + rectangle.h,
-                            match signal.signal().arrow_type() {
-                                ArrowType::Normal => Some(MarkerEnd::ArrowBlock),
-                                ArrowType::Open => Some(MarkerEnd::ArrowOpen),
-                                ArrowType::None => None,
-                            },
-                            signal.signal().line_type() == LineType::Dot,
+                            marker_start,
+                            marker_end,
+                            dash,
                             w,
                         );
                     }
-                } //_ => {}
-            }
+                    group
+                }
+                SignalKind::Note(note, message) => {
+                    let mut group = self
+                        .statement_group("note", layout, note.span())
+                        .with_label(self.row_label(layout, row));
+                    for (i, actor) in note.actors().into_iter().enumerate() {
+                        let name = if i == 0 { "actor" } else { "actor2" };
+                        group = group.with_data(name, actor.as_ref());
+                    }
+                    let (x, width) = layout.note_x(note, rectangle);
+                    let rectangle = Rectangle::new(x, rectangle.y + y + y2, width, rectangle.h);
+                    group.push(self.fill(&Rectangle::new(
+                        rectangle.x + NOTE_MARGIN,
+                        rectangle.y + NOTE_MARGIN,
+                        rectangle.w - NOTE_MARGIN * 2,
+                        rectangle.h - NOTE_MARGIN * 2,
+                    )));
+                    self.draw_text_box(
+                        &mut group,
+                        &rectangle,
+                        message,
+                        NOTE_MARGIN,
+                        self.theme.note_padding,
+                        TextAnchor::Start,
+                        w,
+                    );
+                    group
+                }
+                SignalKind::Section(index, 0) => self.draw_fragment(layout, *index, w),
+                SignalKind::Section(..) | SignalKind::End(_) => continue,
+            };
+            paper.push(group);
         }
+        let signal_height = layout.signals.iter().map(|(_, r)| r.h).sum::<isize>();
+        paper.push_break((y + y2 + signal_height + ROW_BREAK_OFFSET) as f32);
     }
 
     #[allow(clippy::too_many_arguments)]
     fn draw_text(
         &self,
-        paper: &mut Paper,
+        group: &mut Group,
         text: &str,
         x: isize,
         y: isize,
@@ -494,30 +1536,30 @@ impl<'i> SequenceDiagram<'i> {
         } else {
             TextAnchor::End
         };
-        let bbox = text_bbox(text);
+        let bbox = text_bbox(text, &self.theme);
 
-        paper.push(
-            Rect::new(
-                if anchor_left { x } else { x - bbox.w },
-                y,
-                bbox.w,
-                bbox.h + margin + padding,
-            )
-            .with_stroke("none")
-            .with_stroke_width(0)
-            .with_fill("white")
-            .with_fill_opacity(70),
+        let background = Rectangle::new(
+            if anchor_left { x } else { x - bbox.w },
+            y,
+            bbox.w,
+            bbox.h + margin + padding,
         );
-        paper.push(Text::new(x, y, text.to_string()).with_text_anchor(anchor));
+        let fill = Fill::new(self.theme.fill.as_str()).with_opacity(0.7);
+        group.push(self.fill(&background).with_fill(fill));
+        group.push(self.text(x, y, text).with_anchor(anchor));
     }
 
+    /// A box around `text`, its lines aligned to the side or centre given
+    /// by `anchor` and the whole of it centred vertically.
+    #[allow(clippy::too_many_arguments)]
     fn draw_text_box(
         &self,
-        paper: &mut Paper,
+        group: &mut Group,
         rectangle: &Rectangle,
         text: &str,
         margin: isize,
         padding: isize,
+        anchor: TextAnchor,
         ww: &mut Wobble,
     ) {
         let x = rectangle.x + margin;
@@ -525,228 +1567,588 @@ impl<'i> SequenceDiagram<'i> {
         let w = rectangle.w - 2 * margin;
         let h = rectangle.h - 2 * margin;
 
-        //paper.push(Rect::new(x, y, w, h));
-        paper.push(Path::new(format!(
-            "M{},{}{}{}{}{}",
-            x,
-            y,
-            ww.wobble(x, y, x + w, y),
-            ww.wobble(x + w, y, x + w, y + h),
-            ww.wobble(x + w, y + h, x, y + h),
-            ww.wobble(x, y + h, x, y),
-        )));
-        paper.push(Text::new(x + padding, y + padding, text.to_string()));
+        self.draw_rect(group, &Rectangle::new(x, y, w, h), ww);
+        let text_x = match anchor {
+            TextAnchor::Start => x + padding,
+            TextAnchor::Middle => x + w / 2,
+            TextAnchor::End => x + w - padding,
+        };
+        // centred vertically, in a box higher than the text needs
+        let text_y = y + (h - text_bbox(text, &self.theme).h) / 2;
+        group.push(self.text(text_x, text_y, text).with_anchor(anchor));
+    }
+
+    fn draw_rect(&self, group: &mut Group, rectangle: &Rectangle, ww: &mut Wobble) {
+        let Rectangle { x, y, w, h } = *rectangle;
+        let rect = Rect::new(x as f32, y as f32, w as f32, h as f32).with_stroke(self.stroke());
+
+        match self.style {
+            StyleKind::HandDrawn => group.push(Path::new(
+                vec![
+                    Segment::MoveTo(point(x, y)),
+                    ww.wobble(x, y, x + w, y),
+                    ww.wobble(x + w, y, x + w, y + h),
+                    ww.wobble(x + w, y + h, x, y + h),
+                    ww.wobble(x, y + h, x, y),
+                ],
+                self.stroke(),
+            )),
+            StyleKind::Clean => group.push(rect),
+            StyleKind::Rounded => group.push(rect.with_radius(ROUNDED_RADIUS)),
+        }
+    }
+
+    /// Segment from `(x1, y1)` to `(x2, y2)`, the pen being at `(x1, y1)`.
+    fn segment(&self, x1: isize, y1: isize, x2: isize, y2: isize, w: &mut Wobble) -> Segment {
+        match self.style {
+            StyleKind::HandDrawn => w.wobble(x1, y1, x2, y2),
+            StyleKind::Clean | StyleKind::Rounded => Segment::LineTo(point(x2, y2)),
+        }
     }
 
     #[allow(clippy::too_many_arguments)]
     fn draw_line(
         &self,
-        paper: &mut Paper,
+        group: &mut Group,
         x1: isize,
         y1: isize,
         x2: isize,
         y2: isize,
-        marker_end: Option<MarkerEnd>,
+        marker_start: Option<Marker>,
+        marker_end: Option<Marker>,
         dash: bool,
         w: &mut Wobble,
     ) {
-        //let mut path = Path::new(format!("M{},{} L{},{}", x1, y1, x2, y2));
-        let mut path = Path::new(format!("M{},{}{}", x1, y1, w.wobble(x1, y1, x2, y2)));
+        let mut stroke = self.stroke();
+        if dash {
+            stroke = stroke.with_dash(vec![6.0, 2.0]);
+        }
+        let mut path = Path::new(
+            vec![
+                Segment::MoveTo(point(x1, y1)),
+                self.segment(x1, y1, x2, y2, w),
+            ],
+            stroke,
+        );
+        if let Some(marker_start) = marker_start {
+            path = path.with_marker_start(marker_start);
+        }
         if let Some(marker_end) = marker_end {
             path = path.with_marker_end(marker_end);
         }
-        if dash {
-            path = path.with_stroke_dasharray("6px,2px");
-        }
-        paper.push(path);
+        group.push(path);
     }
 }
 
-fn pickle_and_gen(text: &str) -> Result<(String, String), ParseError> {
-    let compressed = compress(text.as_bytes());
-    let pickled = ENCODER.encode(&compressed);
-    let pickled = format!("/v1/{}", pickled);
-    let svg = generate(text)?;
-
-    Ok((pickled, svg))
+/// Draw everything that could be parsed as an SVG document, along with the
+/// errors for the rest.
+pub fn generate(text: &str) -> (String, Vec<ParseError>) {
+    generate_with(text, &RenderOptions::default())
 }
 
-#[derive(Error, Debug)]
-enum LoadAndGenError {
-    #[error("unexpected hash")]
-    UnexpectedHash,
+/// [`generate`] with other than the default options.
+///
+/// The SVG keeps `text`, for [`source_from_svg`] to read back.
+pub fn generate_with(text: &str, options: &RenderOptions) -> (String, Vec<ParseError>) {
+    let (document, errors) = parse_lossy(text);
+    let svg = draw(&document, options).render(SvgBackend::new().with_source(text));
+    (svg, errors)
+}
 
-    #[error("decode error")]
-    DecodeError(#[from] base64::DecodeError),
+/// Parse `text`, failing if any line of it is wrong.
+pub fn parse(text: &str) -> Result<Document<'_>, Diagnostics> {
+    match parse_lossy(text) {
+        (document, errors) if errors.is_empty() => Ok(document),
+        (_, errors) => Err(Diagnostics(errors)),
+    }
+}
 
-    #[error("decompress error")]
-    DecompressError(lz4_compression::decompress::Error),
+/// Everything in `text` that could be parsed, along with an error for each
+/// line that could not.
+pub fn parse_lossy(text: &str) -> (Document<'_>, Vec<ParseError>) {
+    let (document, errors) = parse::parse(text);
+    let errors = errors.into_iter().map(ParseError::from).collect();
+    (document, errors)
+}
 
-    #[error("from utf8 error")]
-    FromUtf8Error(#[from] std::string::FromUtf8Error),
+/// Draw `document` as an SVG document.
+pub fn render(document: &Document<'_>, options: &RenderOptions) -> String {
+    draw(document, options).render(SvgBackend::new())
+}
 
-    #[error("parse error")]
-    ParseError(#[from] ParseError),
+/// Lay `document` out and draw it on a [`Paper`], for any [`Backend`] to
+/// write out.
+pub fn draw(document: &Document<'_>, options: &RenderOptions) -> Paper {
+    SequenceDiagram::new(document, options).draw()
 }
 
-fn load_and_gen(hash: &str) -> Result<(String, String), LoadAndGenError> {
-    if hash.is_empty() {
-        return Ok(("".to_owned(), "".to_owned()));
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbers(numbering: &mut Numbering, n: usize) -> Vec<String> {
+        (0..n).map(|_| numbering.next()).collect()
     }
-    let mut parts = hash.split('/');
-    parts.next();
-    match parts.next() {
-        Some("v1") => {}
-        _ => return Err(LoadAndGenError::UnexpectedHash),
+
+    fn layout<'i>(document: &Document<'i>) -> Layout<'i> {
+        SequenceDiagram::new(document, &RenderOptions::default()).layout()
     }
-    let pickled = if let Some(text) = parts.next() {
-        text
-    } else {
-        return Err(LoadAndGenError::UnexpectedHash);
-    };
-    let compressed = ENCODER.decode(&pickled)?;
-    let text = decompress(&compressed).map_err(LoadAndGenError::DecompressError)?;
-    let text = String::from_utf8(text)?;
 
-    let svg = generate(&text)?;
+    /// Where the lifeline of the `i`th actor is.
+    fn lifeline(layout: &Layout, i: usize) -> isize {
+        layout.actors[i].2.center_x()
+    }
 
-    Ok((text, svg))
-}
+    /// Left edge and width of the note in row `row`.
+    fn note_box(layout: &Layout, row: usize) -> (isize, isize) {
+        match &layout.signals[row] {
+            (SignalKind::Note(note, _), rectangle) => layout.note_x(note, rectangle),
+            _ => panic!("not a note"),
+        }
+    }
 
-fn generate(text: &str) -> Result<String, ParseError> {
-    let result = SequenceDiagram::parse(text)?;
-    Ok(result.draw())
-}
+    #[test]
+    fn notes_beside_and_over_actors() {
+        let text = "A -> B: hello\nnote left of A: left\nnote right of B: right\n\
+                    note over A: over\nnote over B, A: both";
+        let document = parse(text).unwrap();
+        let layout = layout(&document);
+        let (a, b) = (lifeline(&layout, 0), lifeline(&layout, 1));
+
+        let (x, w) = note_box(&layout, 1);
+        assert_eq!(x + w, a + NOTE_OVERLAP);
+        assert!(x >= 0, "off the left edge");
+        let (x, w) = note_box(&layout, 2);
+        assert_eq!(x, b - NOTE_OVERLAP);
+        assert!(x + w <= layout.width, "off the right edge");
+        let (x, w) = note_box(&layout, 3);
+        assert!((x + w / 2 - a).abs() <= 1);
+        let (x, w) = note_box(&layout, 4);
+        assert!(x < a && b < x + w);
+    }
 
-#[wasm_bindgen(module = "codemirror")]
-extern "C" {
-    type CodeMirror;
+    #[test]
+    fn notes_take_rows_of_their_own() {
+        let text = "A -> B: hello\nnote over A: one\\ntwo\nnote over A: one\nB -> A: bye";
+        let document = parse(text).unwrap();
+        let layout = layout(&document);
+        let rows = layout.signals.iter().map(|(_, r)| r).collect::<Vec<_>>();
+        for pair in rows.windows(2) {
+            assert_eq!(pair[1].y, pair[0].y + pair[0].h);
+        }
+        let line_height = layout
+            .theme
+            .font_metrics
+            .line_height(layout.theme.font_size);
+        assert_eq!(rows[1].h - rows[2].h, line_height.ceil() as isize);
+    }
 
-    fn fromTextArea(element: &web_sys::Element, options: &JsValue) -> CodeMirror;
+    /// Distance between the lifelines of the `i`th and next actor.
+    fn gap(layout: &Layout, i: usize) -> isize {
+        lifeline(layout, i + 1) - lifeline(layout, i)
+    }
 
-    #[wasm_bindgen(method)]
-    fn on(this: &CodeMirror, when: &JsValue, f: &JsValue);
+    #[test]
+    fn labels_push_lifelines_apart() {
+        let document = parse("A -> B: hi").unwrap();
+        let short = gap(&layout(&document), 0);
+        let document = parse("A -> B: a label much wider than both actor boxes").unwrap();
+        let layout = layout(&document);
+        assert!(gap(&layout, 0) > short);
+        assert!(gap(&layout, 0) >= layout.signals[0].1.w);
+        assert!(lifeline(&layout, 1) + layout.actors[1].2.w / 2 <= layout.width);
+    }
 
-    #[wasm_bindgen(method)]
-    fn getValue(this: &CodeMirror) -> String;
+    #[test]
+    fn labels_are_spread_over_the_gaps_they_cross() {
+        let text = "A -> B: x\nB -> C: y\nA -> C: a label wide enough to widen both gaps";
+        let document = parse(text).unwrap();
+        let layout = layout(&document);
+        assert!(gap(&layout, 0) + gap(&layout, 1) >= layout.signals[2].1.w);
+        assert!((gap(&layout, 0) - gap(&layout, 1)).abs() <= 1);
+    }
 
-    #[wasm_bindgen(method)]
-    fn setValue(this: &CodeMirror, value: &str);
+    #[test]
+    fn self_signals_widen_the_gap_to_the_right() {
+        let document = parse("A -> B: x\nA -> A: talking to oneself at length").unwrap();
+        let layout = layout(&document);
+        assert!(gap(&layout, 0) >= layout.signals[1].1.w + SELF_SIGNAL_WIDTH);
+    }
 
-    #[wasm_bindgen(method)]
-    fn operation(this: &CodeMirror, f: &dyn Fn());
+    #[test]
+    fn fragments_frame_the_actors_in_them() {
+        let text = "A -> B: x\nB -> C: y\nalt ok\nA -> B: z\nelse not ok\nB -> A: w\nend";
+        let document = parse(text).unwrap();
+        let layout = layout(&document);
+        let (x, w) = layout.fragment_x(0);
+        assert!(x < lifeline(&layout, 0));
+        assert!(lifeline(&layout, 1) < x + w && x + w < lifeline(&layout, 2));
+        // the frame runs from its header to its end, `else` included
+        let rows = layout.fragment_rows(0);
+        assert!(matches!(rows[0].0, SignalKind::Section(0, 0)));
+        assert!(matches!(rows[2].0, SignalKind::Section(0, 1)));
+        assert!(matches!(rows[rows.len() - 1].0, SignalKind::End(0)));
+    }
 
-    #[wasm_bindgen(method)]
-    fn addLineWidget(this: &CodeMirror, line: usize, e: &Element, options: &JsValue) -> Element;
+    #[test]
+    fn nested_fragments_are_inset() {
+        let document = parse("loop\nopt\nA -> B: x\nend\nend").unwrap();
+        let layout = layout(&document);
+        let depths = layout.fragments.iter().map(|(_, depth)| *depth);
+        assert_eq!(depths.collect::<Vec<_>>(), [0, 1]);
+        let (outer_x, outer_w) = layout.fragment_x(0);
+        let (inner_x, inner_w) = layout.fragment_x(1);
+        assert!(outer_x < inner_x);
+        assert!(inner_x + inner_w < outer_x + outer_w);
+    }
 
-    #[wasm_bindgen(method)]
-    fn removeLineWidget(this: &CodeMirror, e: &Element);
-}
+    #[test]
+    fn parse_errors_have_spans() {
+        let text = "A -> B: fine\nA => B: not an arrow";
+        let (_, errors) = parse_lossy(text);
+        assert_eq!(errors.len(), 1);
+        assert_eq!((errors[0].line(), errors[0].column()), (2, 3));
+        // where parsing stopped
+        let span = errors[0].span();
+        assert_eq!((span.start(), span.end()), (15, 15));
+        assert_eq!(span.start_line_col(), (2, 3));
+
+        // the statement, for errors found after parsing
+        let text = "A -> B: fine\nautonumber \"step\"";
+        let (_, errors) = parse_lossy(text);
+        let span = errors[0].span();
+        assert_eq!(&text[span.start()..span.end()], "autonumber \"step\"");
+        assert_eq!(span.end_line_col(), (2, 18));
+    }
 
-#[wasm_bindgen(start)]
-pub fn start() -> Result<(), JsValue> {
-    #[cfg(debug_assertions)]
-    console_error_panic_hook::set_once();
-    wasm_logger::init(Default::default());
+    #[test]
+    fn boxes_fit_multi_line_text() {
+        let theme = Theme::default();
+        let metrics = &theme.font_metrics;
+        let bbox = text_bbox("a\nlonger line\nb", &theme);
+        assert_eq!(bbox.w, metrics.width("longer line", 16).ceil() as isize);
+        let height = metrics.ascent(16) + metrics.descent(16) + 2.0 * metrics.line_height(16);
+        assert_eq!(bbox.h, height.ceil() as isize);
+    }
 
-    let window = web_sys::window().unwrap();
-    let document = window.document().unwrap();
-    let body = document.body().unwrap();
-    let output = document.query_selector("output").unwrap().unwrap();
+    #[test]
+    fn numbering_plain() {
+        let mut numbering = Numbering::new(1, 1, "1").unwrap();
+        assert_eq!(numbers(&mut numbering, 3), ["1", "2", "3"]);
+    }
 
-    let editor = {
-        let options = Object::new();
-        Reflect::set(&options, &JsString::from("lineNumbers"), &JsValue::TRUE).unwrap();
-        Reflect::set(&options, &JsString::from("lineWrapping"), &JsValue::TRUE).unwrap();
-        fromTextArea(
-            &document.query_selector("textarea").unwrap().unwrap(),
-            &options,
-        )
-    };
-    let editor = Rc::new(editor);
-
-    let editor2 = editor.clone();
-    let widgets: Rc<Mutex<Vec<Element>>> = Rc::new(Mutex::new(vec![]));
-    let document = Rc::new(document);
-    let update_annotations = move |infos: Vec<ParseError>| {
-        let editor = editor2.clone();
-        let widgets = widgets.clone();
-        let document = document.clone();
-        editor2.operation(&move || {
-            let mut widgets = widgets.lock().unwrap();
-            for widget in widgets.iter() {
-                editor.removeLineWidget(&widget);
-            }
-            widgets.clear();
-
-            for info in &infos {
-                let message = &info.message;
-                let line = info.line.unwrap_or(0);
-
-                let pre = document.create_element("pre").unwrap();
-                pre.set_text_content(Some(message));
-                let msg = document.create_element("div").unwrap();
-                msg.class_list().add_1("error-msg").unwrap();
-                msg.append_child(&pre).unwrap();
-
-                let opt = Object::new();
-                Reflect::set(&opt, &JsString::from("coverGutter"), &JsValue::TRUE).unwrap();
-                Reflect::set(&opt, &JsString::from("noHScroll"), &JsValue::TRUE).unwrap();
-                let widget = editor.addLineWidget(line - 1, &msg, &opt);
-                widgets.push(widget);
-            }
-        });
-    };
+    #[test]
+    fn numbering_start_and_step() {
+        let mut numbering = Numbering::new(10, 5, "1").unwrap();
+        assert_eq!(numbers(&mut numbering, 3), ["10", "15", "20"]);
+    }
+
+    #[test]
+    fn numbering_prefix_and_suffix() {
+        let mut numbering = Numbering::new(1, 1, "1.").unwrap();
+        assert_eq!(numbers(&mut numbering, 2), ["1.", "2."]);
+        let mut numbering = Numbering::new(1, 1, "Step 1:").unwrap();
+        assert_eq!(numbers(&mut numbering, 2), ["Step 1:", "Step 2:"]);
+    }
+
+    #[test]
+    fn numbering_zero_padded() {
+        let mut numbering = Numbering::new(9, 1, "[01]").unwrap();
+        assert_eq!(numbers(&mut numbering, 2), ["[09]", "[10]"]);
+        let mut numbering = Numbering::new(99, 1, "001").unwrap();
+        assert_eq!(numbers(&mut numbering, 2), ["099", "100"]);
+    }
+
+    #[test]
+    fn numbering_nested() {
+        let mut numbering = Numbering::new(1, 1, "1.1").unwrap();
+        assert_eq!(numbering.next(), "1");
+        numbering.enter();
+        assert_eq!(numbers(&mut numbering, 2), ["2.1", "2.2"]);
+        numbering.enter();
+        assert_eq!(numbering.next(), "2.3.1");
+        numbering.exit();
+        numbering.exit();
+        assert_eq!(numbering.next(), "3");
+    }
+
+    #[test]
+    fn numbering_flat_ignores_fragments() {
+        let mut numbering = Numbering::new(1, 1, "1.").unwrap();
+        numbering.enter();
+        assert_eq!(numbering.next(), "1.");
+        numbering.exit();
+        assert_eq!(numbering.next(), "2.");
+    }
+
+    #[test]
+    fn numbering_without_digits() {
+        assert!(Numbering::new(1, 1, "").is_none());
+        assert!(Numbering::new(1, 1, "step").is_none());
+    }
+
+    #[test]
+    fn huge_theme_sizes_do_not_overflow() {
+        let text = "theme padding 9223372036854775807\ntheme font-size 99999999999\n\
+                    theme note-padding 9223372036854775807\nA -> B: hello\nnote over A: hi";
+        let (document, errors) = parse_lossy(text);
+        assert_eq!(errors.len(), 3);
+        render(&document, &RenderOptions::default());
+        render_text(&document, &TextOptions::default());
+    }
 
-    let hash = window.location().hash().unwrap();
-    if hash.len() > 1 {
-        let (text, svg) = load_and_gen(&hash).unwrap();
-        editor.setValue(&text);
-        output.set_inner_html(&svg);
-    }
-
-    let c = Closure::wrap(Box::new(move |cm: CodeMirror, _| {
-        let text = cm.getValue();
-        body.class_list().add_1("incomplete").unwrap();
-        let (pickled, svg) = match pickle_and_gen(&text) {
-            Ok((pickled, svg)) => (pickled, svg),
-            Err(e) => {
-                log::error!("{:?}", e);
-                update_annotations(vec![e]);
-                return;
+    #[test]
+    fn last_style_wins() {
+        let document = parse("style clean\nstyle rounded").unwrap();
+        let layout = SequenceDiagram::new(&document, &RenderOptions::default()).layout();
+        assert_eq!(layout.style, Some(StyleKind::Rounded));
+    }
+
+    #[test]
+    fn id_prefix_follows_the_drawing() {
+        let prefix = |text| {
+            let svg = generate(text).0;
+            let start = svg.find("seqdia-").unwrap();
+            svg[start..start + 15].to_string()
+        };
+        assert_eq!(prefix("A -> B: hello"), prefix("A -> B: hello"));
+        assert_ne!(prefix("A -> B: hello"), prefix("A -> B: hullo"));
+        assert_ne!(prefix("A -> B: hello"), prefix("A --> B: hello"));
+    }
+
+    fn wrap(text: &str, max_text_width: Option<isize>) -> Cow<'_, str> {
+        let theme = Theme {
+            max_text_width,
+            ..Theme::default()
+        };
+        wrap_text(text, &theme)
+    }
+
+    #[test]
+    fn wrap_between_words() {
+        assert_eq!(wrap("one two three four", Some(80)), "one two\nthree four");
+        assert!(matches!(wrap("one two", Some(80)), Cow::Borrowed(_)));
+    }
+
+    #[test]
+    fn wrap_keeps_long_words_whole() {
+        let text = "a supercalifragilisticexpialidocious word";
+        assert_eq!(
+            wrap(text, Some(50)),
+            "a\nsupercalifragilisticexpialidocious\nword"
+        );
+    }
+
+    #[test]
+    fn wrap_cjk_between_characters() {
+        assert_eq!(wrap("日本語のテキスト", Some(50)), "日本語\nのテキ\nスト");
+    }
+
+    #[test]
+    fn wrap_after_explicit_newlines() {
+        let text = "one two three\nfour five six";
+        assert_eq!(wrap(text, Some(80)), "one two\nthree\nfour five\nsix");
+    }
+
+    #[test]
+    fn wrap_none_leaves_text() {
+        let text = "one two three four five six seven eight nine ten";
+        assert!(matches!(wrap(text, None), Cow::Borrowed(t) if t == text));
+    }
+
+    #[test]
+    fn descriptions_are_not_wrapped() {
+        let text = "theme max-text-width 60\nautonumber\n\
+                    participant A as \"a long name\"\nA -> B: a long message\n\
+                    note over B: a long note";
+        let paper = draw(&parse(text).unwrap(), &RenderOptions::default());
+        assert_eq!(
+            paper.description,
+            "Sequence diagram between a long name and B. \
+             A long name sends '1 a long message' to B. Note over B: a long note."
+        );
+    }
+
+    #[test]
+    fn svg_names_and_describes_the_diagram() {
+        let svg = generate("title: Greeting\nA -> B: hi").0;
+        let start = svg.find("seqdia-").unwrap();
+        let prefix = &svg[start..start + 15];
+        assert!(svg.contains(r#"role="graphics-document document""#));
+        assert!(svg.contains(&format!(r#"aria-labelledby="{}-title""#, prefix)));
+        assert!(svg.contains(&format!(r#"aria-describedby="{}-desc""#, prefix)));
+        assert!(svg.contains(&format!(r#"<title id="{}-title">Greeting</title>"#, prefix)));
+        assert!(svg.contains(&format!(
+            r#"<desc id="{}-desc">Sequence diagram between A and B. A sends 'hi' to B.</desc>"#,
+            prefix
+        )));
+        assert!(svg.contains(r#"role="group" aria-label="A sends 'hi' to B""#));
+        assert!(svg.contains(r#"aria-label="Participant A""#));
+    }
+
+    #[test]
+    fn groups_in_reading_order() {
+        let text = "title: Greeting\nA -> B: hi\nnote over B: n\nB -> A: bye";
+        let paper = draw(&parse(text).unwrap(), &RenderOptions::default());
+        let ids = paper
+            .elements
+            .iter()
+            .filter_map(|element| match element {
+                Element::Group(group) => group.id.as_deref(),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            ids,
+            [
+                "statement-0",
+                "actor-0",
+                "actor-1",
+                "statement-1",
+                "statement-2",
+                "statement-3"
+            ]
+        );
+    }
+
+    /// Groups of the class `class` among `elements`, nested ones included,
+    /// in drawing order.
+    fn groups<'p>(elements: &'p [Element], class: &str) -> Vec<&'p Group> {
+        let mut found = vec![];
+        for element in elements {
+            if let Element::Group(group) = element {
+                if group.class.as_deref() == Some(class) {
+                    found.push(group);
+                }
+                found.extend(groups(&group.elements, class));
             }
+        }
+        found
+    }
+
+    /// The value `group` has for `name` among its data.
+    fn data<'g>(group: &'g Group, name: &str) -> Option<&'g str> {
+        let mut data = group.data.iter();
+        data.find(|(n, _)| n == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    #[test]
+    fn elements_are_grouped_by_what_they_show() {
+        let text = "title: Greeting\nA -> B: hi\nnote over A: n";
+        let paper = draw(&parse(text).unwrap(), &RenderOptions::default());
+        let elements = &paper.elements;
+
+        let title = groups(elements, "title");
+        assert_eq!(title.len(), 1);
+        assert_eq!(title[0].id.as_deref(), Some("statement-0"));
+        let actors = groups(elements, "actor");
+        let names = actors.iter().map(|a| data(a, "actor")).collect::<Vec<_>>();
+        assert_eq!(names, [Some("A"), Some("B")]);
+        assert_eq!(actors[1].id.as_deref(), Some("actor-1"));
+        assert_eq!(groups(elements, "lifeline").len(), 2);
+
+        let signal = groups(elements, "signal");
+        assert_eq!(signal.len(), 1);
+        assert_eq!(signal[0].id.as_deref(), Some("statement-1"));
+        assert_eq!(data(signal[0], "index"), Some("1"));
+        assert_eq!(data(signal[0], "line"), Some("2"));
+        assert_eq!(data(signal[0], "from"), Some("A"));
+        assert_eq!(data(signal[0], "to"), Some("B"));
+        assert_eq!(groups(&signal[0].elements, "signal-label").len(), 1);
+        let note = groups(elements, "note");
+        assert_eq!(note.len(), 1);
+        assert_eq!(data(note[0], "actor"), Some("A"));
+        assert_eq!(data(note[0], "line"), Some("3"));
+    }
+
+    #[test]
+    fn classes_and_data_in_the_svg() {
+        let svg = generate("A -> B: hi").0;
+        assert!(svg.contains(r#"class="actor""#));
+        assert!(svg.contains(r#"data-actor="A""#));
+        assert!(svg.contains(r#"class="signal""#));
+        assert!(svg.contains(r#"data-from="A" data-to="B""#));
+        assert!(svg.contains(r#"class="signal-label""#));
+    }
+
+    #[test]
+    fn activations_from_arrows_and_statements() {
+        let text = "A ->+ B: call\nB -->- A: return\nactivate A\nA -> B: again";
+        let document = parse(text).unwrap();
+        let layout = layout(&document);
+        let rows = layout.signals.iter().map(|(_, r)| r).collect::<Vec<_>>();
+        let bars = layout
+            .activations
+            .iter()
+            .map(|(actor, level, top, bottom)| (actor.as_ref(), *level, *top, *bottom))
+            .collect::<Vec<_>>();
+        let end = rows[2].y + rows[2].h;
+        assert_eq!(
+            bars,
+            [
+                ("B", 0, rows[0].y + rows[0].h, Some(rows[1].y + rows[1].h)),
+                // left open, so it runs to the end
+                ("A", 0, rows[2].y, Some(end)),
+            ]
+        );
+    }
+
+    #[test]
+    fn nested_activations_are_drawn_side_by_side() {
+        let text = "A -> B: one\nactivate B\nA -> B: two\nactivate B\nA -> B: three\n\
+                    deactivate B\ndeactivate B";
+        let document = parse(text).unwrap();
+        let levels = layout(&document)
+            .activations
+            .iter()
+            .map(|a| a.1)
+            .collect::<Vec<_>>();
+        assert_eq!(levels, [0, 1]);
+
+        let paper = draw(&document, &RenderOptions::default());
+        let bars = groups(&paper.elements, "activation")
+            .into_iter()
+            .map(|group| match &group.elements[0] {
+                Element::Rect(rect) => rect.x,
+                _ => panic!("not a bar"),
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(bars.len(), 2);
+        assert_eq!(bars[1] - bars[0], (ACTIVATION_WIDTH / 2) as f32);
+    }
+
+    #[test]
+    fn nested_activations_shift_both_edges() {
+        let text = "A -> B: one\nactivate B\nA -> B: two\nactivate B\nA -> B: three";
+        let document = parse(text).unwrap();
+        let layout = SequenceDiagram::new(&document, &RenderOptions::default()).layout();
+        let b = &layout.actors[1].0;
+        let x = layout.actors[1].2.center_x();
+        let edges = |row: usize| {
+            let y = layout.signals[row].1.y + 1;
+            (
+                layout.lifeline_x(b, y, false),
+                layout.lifeline_x(b, y, true),
+            )
         };
-        body.class_list().remove_1("incomplete").unwrap();
-        update_annotations(vec![]);
-        window.location().set_hash(&pickled).unwrap();
-        output.set_inner_html(&svg);
-    }) as Box<dyn Fn(CodeMirror, JsValue)>);
-
-    editor.on(&JsString::from("change"), c.as_ref());
-    c.forget();
-
-    Ok(())
-}
-
-/*
-fn main() {
-    let input = r###"title titleok
-participant aaa as "A B C"
-aaa -> bbb: ok
-aaa ->> bbb: ok
-aaa - bbb: ok
-aaa --> bbb: ok
-aaa -->> bbb: ok
-aaa -->> aaa: self
-aaa -> aaa: self
-note left of aaa: hello
-note right of aaa: hello2
-note over aaa: hello3
-note over aaa, bbb: hello4
-" aaa" -> bbb: ok2"###;
-
-    let result = SequenceDiagram::parse(input).unwrap();
-    let output = result.draw();
-    println!("{}", output);
-}
-*/
+        assert_eq!(edges(0), (x, x));
+        assert_eq!(edges(1), (x - 5, x + 5));
+        assert_eq!(edges(2), (x, x + 10));
+    }
+
+    #[test]
+    fn autonumber_without_digits_is_an_error() {
+        let text = "autonumber \"step\"\nA -> B: hello";
+        let (document, errors) = parse_lossy(text);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].line(), 1);
+        assert_eq!(errors[0].reason(), "autonumber format `step` has no digits");
+        // numbered as if there were no format
+        let layout = SequenceDiagram::new(&document, &RenderOptions::default()).layout();
+        match &layout.signals[0].0 {
+            SignalKind::Signal(_, _, label) => assert_eq!(label, "1 hello"),
+            _ => panic!("not a signal"),
+        }
+    }
+}
@@ -0,0 +1,378 @@
+use std::env;
+use std::fs;
+use std::io::{self, Read as _, Write as _};
+use std::path::{Path, PathBuf};
+use std::process;
+
+const USAGE: &str = "\
+usage: seqdia [-o OUTPUT] [-f FORMAT] [--scale SCALE] [--font FONT] [--ascii] [FILE]...
+
+Render sequence diagrams to SVG, PNG, PDF or text.
+
+Reads each FILE, or standard input when there is none or FILE is `-`.
+FILE may also be an SVG drawn by seqdia, which keeps the diagram source.
+A single diagram is written to OUTPUT, or to standard output. When there
+are several, each is written next to its FILE with the extension of
+FORMAT, or into the directory OUTPUT. A FILE is never written over: the
+text of `a.txt` goes to `a.seqdia.txt`. Nothing is written when an output
+would be a FILE or when two diagrams would go to the same file.
+
+options:
+    -o, --output OUTPUT    where to write the diagram
+    -f, --format FORMAT    `svg` (the default), `png`, `pdf`, `text`, or
+                           `source` for the text the diagram is drawn from
+        --scale SCALE      pixels per SVG pixel in a PNG, 1 by default
+        --font FONT        measure text with the TrueType or OpenType file
+                           FONT, and name its family in the SVG or embed it
+                           in the PNG or PDF
+        --ascii            draw text with `-`, `|` and `+` instead of
+                           box-drawing characters
+    -h, --help             print this help";
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+enum Format {
+    #[default]
+    Svg,
+    Png,
+    Pdf,
+    Text,
+    Source,
+}
+
+impl Format {
+    fn extension(self) -> &'static str {
+        match self {
+            Self::Svg => "svg",
+            Self::Png => "png",
+            Self::Pdf => "pdf",
+            Self::Text | Self::Source => "txt",
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Args {
+    output: Option<PathBuf>,
+    format: Format,
+    scale: f32,
+    font: Option<PathBuf>,
+    ascii: bool,
+    inputs: Vec<String>,
+}
+
+impl Default for Args {
+    fn default() -> Self {
+        Self {
+            output: None,
+            format: Format::default(),
+            scale: 1.0,
+            font: None,
+            ascii: false,
+            inputs: vec![],
+        }
+    }
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut args = Args::default();
+    let mut iter = env::args().skip(1);
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                println!("{}", USAGE);
+                process::exit(0);
+            }
+            "-o" | "--output" => match iter.next() {
+                Some(output) => args.output = Some(output.into()),
+                None => return Err(format!("`{}` needs a value", arg)),
+            },
+            "-f" | "--format" => match iter.next().as_deref() {
+                Some("svg") => args.format = Format::Svg,
+                Some("png") if cfg!(feature = "png") => args.format = Format::Png,
+                Some("png") => return Err("built without the `png` feature".to_string()),
+                Some("pdf") if cfg!(feature = "pdf") => args.format = Format::Pdf,
+                Some("pdf") => return Err("built without the `pdf` feature".to_string()),
+                Some("text") => args.format = Format::Text,
+                Some("source") => args.format = Format::Source,
+                Some(format) => return Err(format!("unknown format `{}`", format)),
+                None => return Err(format!("`{}` needs a value", arg)),
+            },
+            "--scale" => match iter.next().map(|scale| scale.parse()) {
+                Some(Ok(scale)) if scale > 0.0 => args.scale = scale,
+                Some(_) => return Err(format!("`{}` needs a positive number", arg)),
+                None => return Err(format!("`{}` needs a value", arg)),
+            },
+            "--font" => match iter.next() {
+                Some(font) => args.font = Some(font.into()),
+                None => return Err(format!("`{}` needs a value", arg)),
+            },
+            "--ascii" => args.ascii = true,
+            "--" => {
+                args.inputs.extend(iter);
+                break;
+            }
+            "-" => args.inputs.push(arg),
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+            _ => args.inputs.push(arg),
+        }
+    }
+    if args.inputs.is_empty() {
+        args.inputs.push("-".to_string());
+    }
+    Ok(args)
+}
+
+fn read_input(input: &str) -> io::Result<String> {
+    if input == "-" {
+        let mut text = String::new();
+        io::stdin().read_to_string(&mut text)?;
+        Ok(text)
+    } else {
+        fs::read_to_string(input)
+    }
+}
+
+/// `path` made absolute, through its directory if the file does not exist
+/// yet, so that two names of one file are equal.
+fn resolve(path: &Path) -> PathBuf {
+    if let Ok(path) = fs::canonicalize(path) {
+        return path;
+    }
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    match (fs::canonicalize(dir), path.file_name()) {
+        (Ok(dir), Some(name)) => dir.join(name),
+        _ => path.to_path_buf(),
+    }
+}
+
+/// Whether `a` and `b` name the same file, existing or not.
+fn same_file(a: &Path, b: &Path) -> bool {
+    resolve(a) == resolve(b)
+}
+
+/// Where to write the diagram drawn from `input`, `None` meaning stdout.
+fn output_path(args: &Args, input: &str) -> Option<PathBuf> {
+    if args.inputs.len() == 1 {
+        return args.output.clone();
+    }
+    let extension = args.format.extension();
+    let name = if input == "-" {
+        Path::new("stdin").with_extension(extension)
+    } else {
+        Path::new(input).with_extension(extension)
+    };
+    let mut path = match &args.output {
+        Some(dir) => dir.join(name.file_name().unwrap()),
+        None => name,
+    };
+    // `-f text` on `.txt` files, say, would replace the input otherwise
+    if input != "-" && same_file(Path::new(input), &path) {
+        path.set_extension(format!("seqdia.{}", extension));
+    }
+    Some(path)
+}
+
+/// Where to write the diagram drawn from each input, in order. Fails if one
+/// would be written over an input, or over the diagram drawn from another.
+fn output_paths(args: &Args) -> Result<Vec<Option<PathBuf>>, String> {
+    let outputs = args
+        .inputs
+        .iter()
+        .map(|input| output_path(args, input))
+        .collect::<Vec<_>>();
+    for (i, output) in outputs.iter().enumerate() {
+        let output = match output {
+            Some(output) => output,
+            None => continue,
+        };
+        let mut inputs = args.inputs.iter().filter(|input| *input != "-");
+        if let Some(input) = inputs.find(|input| same_file(Path::new(input), output)) {
+            return Err(format!("`{}` is an input and is not written over", input));
+        }
+        let mut earlier = outputs[..i].iter().map(Option::as_deref);
+        if let Some(j) = earlier.position(|earlier| earlier.is_some_and(|e| same_file(e, output))) {
+            return Err(format!(
+                "the diagrams drawn from `{}` and `{}` would both be written to `{}`",
+                args.inputs[j],
+                args.inputs[i],
+                output.display()
+            ));
+        }
+    }
+    Ok(outputs)
+}
+
+/// Everything needed to draw the diagrams, given `args`.
+#[derive(Debug)]
+struct Renderer {
+    format: Format,
+    options: seqdia::RenderOptions,
+    text: seqdia::TextOptions,
+    #[cfg(feature = "png")]
+    png: seqdia::PngOptions,
+    #[cfg(feature = "pdf")]
+    pdf: seqdia::PdfOptions,
+}
+
+impl Renderer {
+    fn new(args: &Args) -> Result<Self, String> {
+        let mut theme = seqdia::Theme::default();
+        #[cfg(feature = "png")]
+        let mut png = seqdia::PngOptions::new().with_scale(args.scale);
+        #[cfg(feature = "pdf")]
+        let mut pdf = seqdia::PdfOptions::new();
+        if let Some(font) = &args.font {
+            let error = |e: &dyn std::fmt::Display| format!("{}: error: {}", font.display(), e);
+            let data = fs::read(font).map_err(|e| error(&e))?;
+            let metrics = seqdia::FontMetrics::from_font(&data).map_err(|e| error(&e))?;
+            if let Some(family) = metrics.family() {
+                theme.font_family = Some(format!("\"{}\"", family));
+            }
+            theme.font_metrics = metrics;
+            #[cfg(feature = "png")]
+            {
+                png = png.with_font(data.clone());
+            }
+            #[cfg(feature = "pdf")]
+            {
+                pdf = pdf.with_font(data);
+            }
+        }
+        Ok(Self {
+            format: args.format,
+            options: seqdia::RenderOptions::new().with_theme(theme),
+            text: seqdia::TextOptions::new().with_ascii(args.ascii),
+            #[cfg(feature = "png")]
+            png,
+            #[cfg(feature = "pdf")]
+            pdf,
+        })
+    }
+
+    /// Draw `document`, parsed from `text`.
+    fn draw(&self, document: &seqdia::Document<'_>, text: &str) -> Result<Vec<u8>, String> {
+        match self.format {
+            Format::Svg => {
+                let paper = seqdia::draw(document, &self.options);
+                Ok(paper
+                    .render(seqdia::SvgBackend::new().with_source(text))
+                    .into_bytes())
+            }
+            #[cfg(feature = "png")]
+            Format::Png => {
+                seqdia::render_png(document, &self.options, &self.png).map_err(|e| e.to_string())
+            }
+            #[cfg(feature = "pdf")]
+            Format::Pdf => {
+                seqdia::render_pdf(document, &self.options, &self.pdf).map_err(|e| e.to_string())
+            }
+            Format::Text => Ok(seqdia::render_text(document, &self.text).into_bytes()),
+            Format::Source => Ok(text.as_bytes().to_vec()),
+            #[cfg(not(feature = "png"))]
+            Format::Png => unreachable!("rejected by parse_args"),
+            #[cfg(not(feature = "pdf"))]
+            Format::Pdf => unreachable!("rejected by parse_args"),
+        }
+    }
+}
+
+fn write_output(output: Option<&Path>, data: &[u8]) -> io::Result<()> {
+    match output {
+        Some(path) => fs::write(path, data),
+        None => {
+            let mut stdout = io::stdout().lock();
+            stdout.write_all(data)?;
+            stdout.flush()
+        }
+    }
+}
+
+/// Render `input`, reporting any problem on stderr. Returns whether it went
+/// through without errors.
+fn render(renderer: &Renderer, input: &str, output: Option<&Path>) -> bool {
+    let name = if input == "-" { "<stdin>" } else { input };
+    let mut text = match read_input(input) {
+        Ok(text) => text,
+        Err(e) => {
+            eprintln!("{}: error: {}", name, e);
+            return false;
+        }
+    };
+    let start = text.trim_start();
+    if start.starts_with("<svg") || start.starts_with("<?xml") {
+        text = match seqdia::source_from_svg(&text) {
+            Ok(source) => source,
+            Err(e) => {
+                eprintln!("{}: error: {}", name, e);
+                return false;
+            }
+        };
+    }
+
+    // whatever could be parsed is still written, as the editor does
+    let (document, errors) = seqdia::parse_lossy(&text);
+    for error in &errors {
+        eprintln!(
+            "{}:{}:{}: error: {}",
+            name,
+            error.line(),
+            error.column(),
+            error.reason()
+        );
+    }
+    let data = match renderer.draw(&document, &text) {
+        Ok(data) => data,
+        Err(e) => {
+            eprintln!("{}: error: {}", name, e);
+            return false;
+        }
+    };
+
+    if let Err(e) = write_output(output, &data) {
+        let output = output.unwrap_or_else(|| Path::new("<stdout>"));
+        eprintln!("{}: error: {}", output.display(), e);
+        return false;
+    }
+    errors.is_empty()
+}
+
+fn main() {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("seqdia: {}\n\n{}", e, USAGE);
+            process::exit(2);
+        }
+    };
+    if args.inputs.iter().filter(|input| *input == "-").count() > 1 {
+        eprintln!("seqdia: standard input can only be read once");
+        process::exit(2);
+    }
+
+    let outputs = match output_paths(&args) {
+        Ok(outputs) => outputs,
+        Err(e) => {
+            eprintln!("seqdia: {}", e);
+            process::exit(2);
+        }
+    };
+
+    let renderer = match Renderer::new(&args) {
+        Ok(renderer) => renderer,
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(1);
+        }
+    };
+
+    let mut ok = true;
+    for (input, output) in args.inputs.iter().zip(&outputs) {
+        ok &= render(&renderer, input, output.as_deref());
+    }
+    if !ok {
+        process::exit(1);
+    }
+}
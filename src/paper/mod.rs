@@ -0,0 +1,532 @@
+//! What a diagram is drawn as: shapes, text and groups of them, ready for a
+//! [`Backend`] to write out in some format.
+
+#[cfg(feature = "pdf")]
+mod pdf;
+mod svg;
+
+#[cfg(feature = "pdf")]
+pub(crate) use pdf::{PageSetup, PdfBackend};
+pub use svg::{SvgBackend, SvgSize};
+
+use crate::Theme;
+
+/// An output format, drawing a [`Paper`] one element after another.
+///
+/// [`Paper::render`] calls [`Backend::begin`], then the method for each
+/// element in drawing order, then [`Backend::finish`].
+pub trait Backend {
+    type Output;
+
+    /// Start drawing on a paper of its size and theme, before any element.
+    fn begin(&mut self, paper: &Paper);
+
+    fn begin_group(&mut self, _group: &Group) {}
+
+    fn end_group(&mut self, _group: &Group) {}
+
+    fn path(&mut self, path: &Path);
+
+    fn rect(&mut self, rect: &Rect);
+
+    fn text(&mut self, text: &Text);
+
+    fn finish(self) -> Self::Output;
+}
+
+#[derive(Debug)]
+pub(crate) struct PaperBuilder {
+    width: f32,
+    height: f32,
+    theme: Theme,
+}
+
+impl Default for PaperBuilder {
+    fn default() -> Self {
+        Self {
+            width: 512.0,
+            height: 342.0,
+            theme: Theme::default(),
+        }
+    }
+}
+
+impl PaperBuilder {
+    pub(crate) fn width(&mut self, width: f32) -> &mut Self {
+        self.width = width;
+        self
+    }
+
+    pub(crate) fn height(&mut self, height: f32) -> &mut Self {
+        self.height = height;
+        self
+    }
+
+    pub(crate) fn theme(&mut self, theme: Theme) -> &mut Self {
+        self.theme = theme;
+        self
+    }
+
+    pub(crate) fn build(&mut self) -> Paper {
+        Paper {
+            width: self.width,
+            height: self.height,
+            title: None,
+            description: String::new(),
+            theme: self.theme.clone(),
+            elements: vec![],
+            breaks: vec![],
+        }
+    }
+}
+
+/// A position, in pixels from the top left of the paper.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Point {
+    pub x: f32,
+    pub y: f32,
+}
+
+impl Point {
+    pub fn new(x: f32, y: f32) -> Self {
+        Self { x, y }
+    }
+}
+
+/// How a line is drawn.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct Stroke {
+    pub color: String,
+    /// in pixels
+    pub width: f32,
+    /// lengths of the dashes and the gaps between them, solid if empty
+    pub dash: Vec<f32>,
+}
+
+impl Stroke {
+    pub(crate) fn new(color: impl Into<String>, width: f32) -> Self {
+        Self {
+            color: color.into(),
+            width,
+            dash: vec![],
+        }
+    }
+
+    pub(crate) fn with_dash(self, dash: Vec<f32>) -> Self {
+        Self { dash, ..self }
+    }
+}
+
+/// How a shape is filled.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct Fill {
+    pub color: String,
+    /// from 0, transparent, to 1
+    pub opacity: f32,
+}
+
+impl Fill {
+    pub(crate) fn new(color: impl Into<String>) -> Self {
+        Self {
+            color: color.into(),
+            opacity: 1.0,
+        }
+    }
+
+    pub(crate) fn with_opacity(self, opacity: f32) -> Self {
+        Self { opacity, ..self }
+    }
+}
+
+/// Font and color of a text, with the font's vertical metrics at its size.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct TextStyle {
+    /// CSS `font-family`, inherited from the page if `None`
+    pub font_family: Option<String>,
+    /// in pixels
+    pub font_size: f32,
+    pub color: String,
+    /// from the top of a line to its baseline
+    pub ascent: f32,
+    /// from one baseline to the next
+    pub line_height: f32,
+}
+
+impl TextStyle {
+    pub(crate) fn from_theme(theme: &Theme) -> Self {
+        let metrics = &theme.font_metrics;
+        Self {
+            font_family: theme.font_family.clone(),
+            font_size: theme.font_size as f32,
+            color: theme.text_color.clone(),
+            ascent: metrics.ascent(theme.font_size),
+            line_height: metrics.line_height(theme.font_size),
+        }
+    }
+}
+
+/// Which end of a line of text is at its `x`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextAnchor {
+    Start,
+    Middle,
+    End,
+}
+
+/// Lines of text, one under the other.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct Text {
+    pub x: f32,
+    /// top of the first line
+    pub y: f32,
+    pub lines: Vec<String>,
+    pub anchor: TextAnchor,
+    pub style: TextStyle,
+}
+
+impl From<Text> for Element {
+    fn from(v: Text) -> Self {
+        Self::Text(v)
+    }
+}
+
+impl Text {
+    pub(crate) fn new(x: f32, y: f32, text: &str, style: TextStyle) -> Self {
+        Self {
+            x,
+            y,
+            lines: text.lines().map(str::to_string).collect(),
+            anchor: TextAnchor::Start,
+            style,
+        }
+    }
+
+    pub(crate) fn with_anchor(self, anchor: TextAnchor) -> Self {
+        Self { anchor, ..self }
+    }
+
+    /// Each line with the `y` of its baseline.
+    pub fn baselines(&self) -> impl Iterator<Item = (f32, &str)> + '_ {
+        self.lines.iter().enumerate().map(move |(i, line)| {
+            let y = self.y + self.style.ascent + i as f32 * self.style.line_height;
+            (y, line.as_str())
+        })
+    }
+}
+
+/// Arrowheads at either end of a path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Marker {
+    /// a filled triangle
+    ArrowBlock,
+    /// a chevron
+    ArrowOpen,
+}
+
+impl Marker {
+    /// Corners of the arrowhead, pointing right along the x axis.
+    pub fn outline(self) -> &'static [(f32, f32)] {
+        match self {
+            Self::ArrowBlock => &[(0.0, 0.0), (5.0, 2.5), (0.0, 5.0)],
+            Self::ArrowOpen => &[
+                (9.6, 8.0),
+                (1.92, 16.0),
+                (0.0, 13.7),
+                (5.76, 8.0),
+                (0.0, 2.286),
+                (1.92, 0.0),
+            ],
+        }
+    }
+
+    /// Width and height of the box around [`Marker::outline`].
+    pub fn view_box(self) -> (f32, f32) {
+        match self {
+            Self::ArrowBlock => (5.0, 5.0),
+            Self::ArrowOpen => (9.6, 16.0),
+        }
+    }
+
+    /// Width and height drawn, in widths of the line.
+    pub fn size(self) -> (f32, f32) {
+        match self {
+            Self::ArrowBlock => (5.0, 5.0),
+            Self::ArrowOpen => (4.0, 16.0),
+        }
+    }
+
+    /// The point of the outline placed at the end of the path.
+    pub fn reference(self) -> Point {
+        match self {
+            Self::ArrowBlock => Point::new(5.0, 2.5),
+            Self::ArrowOpen => Point::new(9.6, 8.0),
+        }
+    }
+}
+
+/// A piece of a path, starting where the one before ended.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Segment {
+    MoveTo(Point),
+    LineTo(Point),
+    /// a cubic Bézier curve through two control points
+    CubicTo(Point, Point, Point),
+    Close,
+}
+
+/// Lines and curves, not filled.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct Path {
+    pub segments: Vec<Segment>,
+    pub stroke: Stroke,
+    /// pointing back from the start of the path
+    pub marker_start: Option<Marker>,
+    pub marker_end: Option<Marker>,
+}
+
+impl From<Path> for Element {
+    fn from(v: Path) -> Self {
+        Self::Path(v)
+    }
+}
+
+impl Path {
+    pub(crate) fn new(segments: Vec<Segment>, stroke: Stroke) -> Self {
+        Self {
+            segments,
+            stroke,
+            marker_start: None,
+            marker_end: None,
+        }
+    }
+
+    pub(crate) fn with_marker_start(self, marker_start: Marker) -> Self {
+        let marker_start = Some(marker_start);
+        Self {
+            marker_start,
+            ..self
+        }
+    }
+
+    pub(crate) fn with_marker_end(self, marker_end: Marker) -> Self {
+        let marker_end = Some(marker_end);
+        Self { marker_end, ..self }
+    }
+
+    /// End and start of the first line or curve, the direction a marker at
+    /// its start points in.
+    pub fn first_segment(&self) -> Option<(Point, Point)> {
+        let mut current = None;
+        for segment in &self.segments {
+            match (*segment, current) {
+                (Segment::MoveTo(p), _) => current = Some(p),
+                (Segment::LineTo(p), Some(start)) if p != start => return Some((p, start)),
+                (Segment::CubicTo(c1, _, p), Some(start)) => {
+                    // the direction at the start of a curve is that of its first control point
+                    let next = if c1 == start { p } else { c1 };
+                    return (next != start).then_some((next, start));
+                }
+                _ => {}
+            }
+        }
+        None
+    }
+
+    /// Start and end of the last line or curve, the direction a marker at
+    /// its end points in.
+    pub fn last_segment(&self) -> Option<(Point, Point)> {
+        let mut start = Point::new(0.0, 0.0);
+        let mut current = start;
+        for segment in &self.segments {
+            match *segment {
+                Segment::MoveTo(p) => current = p,
+                Segment::LineTo(p) => {
+                    start = current;
+                    current = p;
+                }
+                Segment::CubicTo(_, c2, p) => {
+                    // the direction at the end of a curve is that of its last control point
+                    start = if c2 == p { current } else { c2 };
+                    current = p;
+                }
+                Segment::Close => {}
+            }
+        }
+        (start != current).then_some((start, current))
+    }
+}
+
+/// A rectangle, with rounded corners if `radius` is not 0.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct Rect {
+    pub x: f32,
+    pub y: f32,
+    pub width: f32,
+    pub height: f32,
+    pub radius: f32,
+    pub fill: Option<Fill>,
+    pub stroke: Option<Stroke>,
+}
+
+impl From<Rect> for Element {
+    fn from(v: Rect) -> Self {
+        Self::Rect(v)
+    }
+}
+
+impl Rect {
+    pub(crate) fn new(x: f32, y: f32, width: f32, height: f32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+            radius: 0.0,
+            fill: None,
+            stroke: None,
+        }
+    }
+
+    pub(crate) fn with_radius(self, radius: f32) -> Self {
+        Self { radius, ..self }
+    }
+
+    pub(crate) fn with_fill(self, fill: Fill) -> Self {
+        let fill = Some(fill);
+        Self { fill, ..self }
+    }
+
+    pub(crate) fn with_stroke(self, stroke: Stroke) -> Self {
+        let stroke = Some(stroke);
+        Self { stroke, ..self }
+    }
+}
+
+/// Elements that belong together, such as everything drawn for one signal.
+#[derive(Debug, Clone, Default, PartialEq)]
+#[non_exhaustive]
+pub struct Group {
+    pub elements: Vec<Element>,
+    /// what the elements are, such as `actor`, `signal` or `note`
+    pub class: Option<String>,
+    /// unique in the paper, such as `actor-0` or `statement-3`; backends
+    /// scope it to the document they write
+    pub id: Option<String>,
+    /// names and values describing what is drawn, such as `("actor", "Alice")`
+    pub data: Vec<(String, String)>,
+    /// what the elements show, in words, for screen readers
+    pub label: Option<String>,
+}
+
+impl From<Group> for Element {
+    fn from(v: Group) -> Self {
+        Self::Group(v)
+    }
+}
+
+impl Group {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn with_class(self, class: &str) -> Self {
+        let class = Some(class.to_string());
+        Self { class, ..self }
+    }
+
+    pub(crate) fn with_id(self, id: String) -> Self {
+        let id = Some(id);
+        Self { id, ..self }
+    }
+
+    pub(crate) fn with_label(self, label: String) -> Self {
+        let label = Some(label);
+        Self { label, ..self }
+    }
+
+    pub(crate) fn with_data(mut self, name: &str, value: impl ToString) -> Self {
+        self.data.push((name.to_string(), value.to_string()));
+        self
+    }
+
+    pub(crate) fn push(&mut self, element: impl Into<Element>) {
+        self.elements.push(element.into());
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum Element {
+    Path(Path),
+    Rect(Rect),
+    Text(Text),
+    Group(Group),
+}
+
+impl Element {
+    /// Draw this element, and those in it, with `backend`.
+    pub fn accept<B: Backend + ?Sized>(&self, backend: &mut B) {
+        match self {
+            Self::Path(e) => backend.path(e),
+            Self::Rect(e) => backend.rect(e),
+            Self::Text(e) => backend.text(e),
+            Self::Group(e) => {
+                backend.begin_group(e);
+                for element in &e.elements {
+                    element.accept(backend);
+                }
+                backend.end_group(e);
+            }
+        }
+    }
+}
+
+/// A drawn diagram, laid out once and then written by any [`Backend`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub struct Paper {
+    /// in pixels
+    pub width: f32,
+    /// in pixels
+    pub height: f32,
+    /// the `title` of the diagram
+    pub title: Option<String>,
+    /// what the diagram shows, in plain sentences, for screen readers
+    pub description: String,
+    /// what the diagram was drawn with; backends draw its `background`
+    pub theme: Theme,
+    /// in drawing order, later ones on top
+    pub elements: Vec<Element>,
+    /// `y` of the gaps between rows, where a page may end
+    pub breaks: Vec<f32>,
+}
+
+impl Paper {
+    pub(crate) fn builder() -> PaperBuilder {
+        PaperBuilder::default()
+    }
+
+    /// Draw everything with `backend`.
+    pub fn render<B: Backend>(&self, mut backend: B) -> B::Output {
+        backend.begin(self);
+        for element in &self.elements {
+            element.accept(&mut backend);
+        }
+        backend.finish()
+    }
+
+    pub(crate) fn push(&mut self, element: impl Into<Element>) {
+        self.elements.push(element.into());
+    }
+
+    pub(crate) fn push_break(&mut self, y: f32) {
+        self.breaks.push(y);
+    }
+}
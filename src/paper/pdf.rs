@@ -0,0 +1,444 @@
+//! Writing a [`Paper`] as vector PDF, split into pages between rows.
+
+use std::collections::BTreeMap;
+use std::str::FromStr as _;
+
+use pdf_writer::types::{CidFontType, FontFlags, SystemInfo, UnicodeCmap};
+use pdf_writer::{Content, Finish as _, Name, Pdf, Rect as PdfRect, Ref, Str};
+use ttf_parser::{Face, GlyphId};
+
+use super::{Backend, Fill, Paper, Path, Point, Rect, Segment, Stroke, Text, TextAnchor};
+use crate::FontError;
+
+/// SVG pixels are 1/96 inch, PDF points 1/72.
+const POINTS_PER_PIXEL: f32 = 0.75;
+
+/// Bezier control point distance for a quarter circle of radius 1.
+const KAPPA: f32 = 0.552_284_8;
+
+const FONT_NAME: Name<'static> = Name(b"F1");
+
+const SYSTEM_INFO: SystemInfo<'static> = SystemInfo {
+    registry: Str(b"Adobe"),
+    ordering: Str(b"Identity"),
+    supplement: 0,
+};
+
+/// Size of the pages and of the margin around the diagram, in points.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PageSetup {
+    pub(crate) width: f32,
+    pub(crate) height: f32,
+    pub(crate) margin: f32,
+}
+
+/// The font all text is drawn with, and the glyphs it was drawn with so far.
+struct Font<'f> {
+    face: Face<'f>,
+    used: BTreeMap<u16, char>,
+}
+
+impl<'f> Font<'f> {
+    fn glyphs(&mut self, text: &str) -> Vec<u8> {
+        let mut glyphs = Vec::with_capacity(text.len() * 2);
+        for c in text.chars() {
+            let glyph = self.face.glyph_index(c).unwrap_or(GlyphId(0));
+            self.used.entry(glyph.0).or_insert(c);
+            glyphs.extend_from_slice(&glyph.0.to_be_bytes());
+        }
+        glyphs
+    }
+
+    /// In units of 1/1000 em, as PDF font widths are.
+    fn advance(&self, glyph: u16) -> f32 {
+        let advance = self.face.glyph_hor_advance(GlyphId(glyph)).unwrap_or(0);
+        self.to_thousandths(advance as f32)
+    }
+
+    fn to_thousandths(&self, units: f32) -> f32 {
+        units * 1000.0 / self.face.units_per_em() as f32
+    }
+
+    fn width(&self, line: &str, size: f32) -> f32 {
+        let units = line
+            .chars()
+            .filter_map(|c| self.face.glyph_index(c))
+            .filter_map(|glyph| self.face.glyph_hor_advance(glyph))
+            .map(|advance| advance as f32)
+            .sum::<f32>();
+        units * size / self.face.units_per_em() as f32
+    }
+}
+
+/// `color` as RGB in 0..1, `None` if it is `none` or cannot be read.
+fn rgb(color: &str) -> Option<[f32; 3]> {
+    if color == "none" {
+        return None;
+    }
+    let color = svgtypes::Color::from_str(color).ok()?;
+    Some([
+        color.red as f32 / 255.0,
+        color.green as f32 / 255.0,
+        color.blue as f32 / 255.0,
+    ])
+}
+
+/// Pages as the range of `y` each shows, ending them between rows where
+/// possible.
+fn pages(paper: &Paper, page_height: f32) -> Vec<(f32, f32)> {
+    let mut pages = vec![];
+    let mut top = 0.0;
+    while paper.height - top > page_height {
+        let bottom = paper
+            .breaks
+            .iter()
+            .copied()
+            .filter(|y| *y > top && *y <= top + page_height)
+            .max_by(f32::total_cmp)
+            .unwrap_or(top + page_height);
+        pages.push((top, bottom));
+        top = bottom;
+    }
+    pages.push((top, paper.height));
+    pages
+}
+
+/// Draws a [`Paper`] as a PDF document, every element on every page and
+/// each page clipped to its share of the diagram.
+pub(crate) struct PdfBackend<'f> {
+    setup: PageSetup,
+    font: Font<'f>,
+    pages: Vec<Content>,
+    /// fill opacities used, in percent, each with its graphics state
+    opacities: Vec<usize>,
+}
+
+impl<'f> PdfBackend<'f> {
+    pub(crate) fn new(setup: PageSetup, font: &'f [u8]) -> Result<Self, FontError> {
+        let font = Font {
+            face: Face::parse(font, 0)?,
+            used: BTreeMap::new(),
+        };
+        Ok(Self {
+            setup,
+            font,
+            pages: vec![],
+            opacities: vec![],
+        })
+    }
+
+    fn outline(content: &mut Content, rect: &Rect) {
+        let (x, y, w, h) = (rect.x, rect.y, rect.width, rect.height);
+        let r = rect.radius.min(w / 2.0).min(h / 2.0);
+        if r <= 0.0 {
+            content.rect(x, y, w, h);
+            return;
+        }
+        let c = r * (1.0 - KAPPA);
+        content.move_to(x + r, y);
+        content.line_to(x + w - r, y);
+        content.cubic_to(x + w - c, y, x + w, y + c, x + w, y + r);
+        content.line_to(x + w, y + h - r);
+        content.cubic_to(x + w, y + h - c, x + w - c, y + h, x + w - r, y + h);
+        content.line_to(x + r, y + h);
+        content.cubic_to(x + c, y + h, x, y + h - c, x, y + h - r);
+        content.line_to(x, y + r);
+        content.cubic_to(x, y + c, x + c, y, x + r, y);
+        content.close_path();
+    }
+
+    /// Set the stroke color and style, `false` if nothing is to be drawn.
+    fn stroke(content: &mut Content, stroke: &Stroke) -> bool {
+        let [r, g, b] = match rgb(&stroke.color) {
+            Some(color) if stroke.width > 0.0 => color,
+            _ => return false,
+        };
+        content.set_stroke_rgb(r, g, b);
+        content.set_line_width(stroke.width);
+        if !stroke.dash.is_empty() {
+            content.set_dash_pattern(stroke.dash.iter().copied(), 0.0);
+        }
+        true
+    }
+
+    /// Set the fill color and opacity, `false` if nothing is to be drawn.
+    fn fill(&mut self, page: usize, fill: &Fill) -> bool {
+        let [r, g, b] = match rgb(&fill.color) {
+            Some(color) => color,
+            None => return false,
+        };
+        let content = &mut self.pages[page];
+        if fill.opacity < 1.0 {
+            let opacity = (fill.opacity * 100.0).round() as usize;
+            if !self.opacities.contains(&opacity) {
+                self.opacities.push(opacity);
+            }
+            content.set_parameters(Name(format!("A{}", opacity).as_bytes()));
+        }
+        content.set_fill_rgb(r, g, b);
+        true
+    }
+}
+
+impl Backend for PdfBackend<'_> {
+    type Output = Vec<u8>;
+
+    fn begin(&mut self, paper: &Paper) {
+        let setup = self.setup;
+        let scale = f32::min(
+            POINTS_PER_PIXEL,
+            (setup.width - 2.0 * setup.margin) / paper.width,
+        );
+        let page_height = (setup.height - 2.0 * setup.margin) / scale;
+
+        for (top, bottom) in pages(paper, page_height) {
+            let mut content = Content::new();
+            content.save_state();
+            content.transform([
+                scale,
+                0.0,
+                0.0,
+                -scale,
+                setup.margin,
+                setup.height - setup.margin + top * scale,
+            ]);
+            content.rect(0.0, top, paper.width, bottom - top);
+            content.clip_nonzero();
+            content.end_path();
+            self.pages.push(content);
+        }
+
+        if let Some(background) = &paper.theme.background {
+            self.rect(
+                &Rect::new(0.0, 0.0, paper.width, paper.height)
+                    .with_fill(Fill::new(background.as_str())),
+            );
+        }
+    }
+
+    fn path(&mut self, path: &Path) {
+        let markers = [
+            path.marker_start.zip(path.first_segment()),
+            path.marker_end.zip(path.last_segment()),
+        ];
+        for content in &mut self.pages {
+            content.save_state();
+            if !Self::stroke(content, &path.stroke) {
+                content.restore_state();
+                return;
+            }
+            for segment in &path.segments {
+                match *segment {
+                    Segment::MoveTo(p) => {
+                        content.move_to(p.x, p.y);
+                    }
+                    Segment::LineTo(p) => {
+                        content.line_to(p.x, p.y);
+                    }
+                    Segment::CubicTo(c1, c2, p) => {
+                        content.cubic_to(c1.x, c1.y, c2.x, c2.y, p.x, p.y);
+                    }
+                    Segment::Close => {
+                        content.close_path();
+                    }
+                }
+            }
+            content.stroke();
+            content.restore_state();
+
+            for (marker, (start, Point { x, y })) in markers.iter().flatten().copied() {
+                let angle = (y - start.y).atan2(x - start.x);
+                let (sin, cos) = angle.sin_cos();
+                // markers are drawn in widths of the line, as in SVG
+                let k = path.stroke.width * marker.size().0 / marker.view_box().0;
+                let reference = marker.reference();
+                let [r, g, b] = rgb(&path.stroke.color).unwrap_or_default();
+
+                content.save_state();
+                content.transform([cos * k, sin * k, -sin * k, cos * k, x, y]);
+                content.transform([1.0, 0.0, 0.0, 1.0, -reference.x, -reference.y]);
+                content.set_fill_rgb(r, g, b);
+                for (i, (x, y)) in marker.outline().iter().enumerate() {
+                    if i == 0 {
+                        content.move_to(*x, *y);
+                    } else {
+                        content.line_to(*x, *y);
+                    }
+                }
+                content.close_path();
+                content.fill_nonzero();
+                content.restore_state();
+            }
+        }
+    }
+
+    fn rect(&mut self, rect: &Rect) {
+        for page in 0..self.pages.len() {
+            if let Some(fill) = &rect.fill {
+                self.pages[page].save_state();
+                if self.fill(page, fill) {
+                    Self::outline(&mut self.pages[page], rect);
+                    self.pages[page].fill_nonzero();
+                }
+                self.pages[page].restore_state();
+            }
+
+            if let Some(stroke) = &rect.stroke {
+                let content = &mut self.pages[page];
+                content.save_state();
+                if Self::stroke(content, stroke) {
+                    Self::outline(content, rect);
+                    content.stroke();
+                }
+                content.restore_state();
+            }
+        }
+    }
+
+    fn text(&mut self, text: &Text) {
+        let style = &text.style;
+        let [r, g, b] = rgb(&style.color).unwrap_or_default();
+        for content in &mut self.pages {
+            content.set_fill_rgb(r, g, b);
+            for (baseline, line) in text.baselines() {
+                let x = match text.anchor {
+                    TextAnchor::Start => text.x,
+                    TextAnchor::Middle => text.x - self.font.width(line, style.font_size) / 2.0,
+                    TextAnchor::End => text.x - self.font.width(line, style.font_size),
+                };
+                content.begin_text();
+                content.set_font(FONT_NAME, style.font_size);
+                // the page is flipped to have y grow downwards, as in SVG
+                content.set_text_matrix([1.0, 0.0, 0.0, -1.0, x, baseline]);
+                content.show(Str(&self.font.glyphs(line)));
+                content.end_text();
+            }
+        }
+    }
+
+    fn finish(self) -> Vec<u8> {
+        let contents = self
+            .pages
+            .into_iter()
+            .map(|mut content| {
+                content.restore_state();
+                content.finish()
+            })
+            .collect();
+        write_document(self.setup, contents, &self.opacities, &self.font)
+    }
+}
+
+fn write_document(
+    setup: PageSetup,
+    contents: Vec<Vec<u8>>,
+    opacities: &[usize],
+    font: &Font<'_>,
+) -> Vec<u8> {
+    let mut next = 0;
+    let mut alloc = || {
+        next += 1;
+        Ref::new(next)
+    };
+    let catalog_id = alloc();
+    let pages_id = alloc();
+    let type0_id = alloc();
+    let cid_id = alloc();
+    let descriptor_id = alloc();
+    let cmap_id = alloc();
+    let file_id = alloc();
+    let opacity_ids = opacities.iter().map(|_| alloc()).collect::<Vec<_>>();
+    let page_ids = contents
+        .iter()
+        .map(|_| (alloc(), alloc()))
+        .collect::<Vec<_>>();
+
+    let mut pdf = Pdf::new();
+    pdf.catalog(catalog_id).pages(pages_id);
+    pdf.pages(pages_id)
+        .kids(page_ids.iter().map(|(page, _)| *page))
+        .count(page_ids.len() as i32);
+
+    for ((page_id, content_id), content) in page_ids.iter().zip(&contents) {
+        let mut page = pdf.page(*page_id);
+        page.media_box(PdfRect::new(0.0, 0.0, setup.width, setup.height));
+        page.parent(pages_id);
+        page.contents(*content_id);
+        let mut resources = page.resources();
+        resources.fonts().pair(FONT_NAME, type0_id);
+        let mut states = resources.ext_g_states();
+        for (opacity, id) in opacities.iter().zip(&opacity_ids) {
+            states.pair(Name(format!("A{}", opacity).as_bytes()), *id);
+        }
+        states.finish();
+        resources.finish();
+        page.finish();
+        pdf.stream(*content_id, content);
+    }
+    for (opacity, id) in opacities.iter().zip(&opacity_ids) {
+        pdf.ext_graphics(*id)
+            .non_stroking_alpha(*opacity as f32 / 100.0);
+    }
+
+    let face = &font.face;
+    let name = face
+        .names()
+        .into_iter()
+        .filter(|name| name.name_id == ttf_parser::name_id::POST_SCRIPT_NAME)
+        .find_map(|name| name.to_string())
+        .unwrap_or_else(|| "Font".to_string());
+    let name = Name(name.as_bytes());
+
+    pdf.type0_font(type0_id)
+        .base_font(name)
+        .encoding_predefined(Name(b"Identity-H"))
+        .descendant_font(cid_id)
+        .to_unicode(cmap_id);
+
+    let mut cid = pdf.cid_font(cid_id);
+    cid.subtype(CidFontType::Type2)
+        .base_font(name)
+        .system_info(SYSTEM_INFO)
+        .font_descriptor(descriptor_id)
+        .default_width(0.0)
+        .cid_to_gid_map_predefined(Name(b"Identity"));
+    let mut widths = cid.widths();
+    for glyph in font.used.keys() {
+        widths.consecutive(*glyph, [font.advance(*glyph)]);
+    }
+    widths.finish();
+    cid.finish();
+
+    let bbox = face.global_bounding_box();
+    let mut flags = FontFlags::SYMBOLIC;
+    if face.is_monospaced() {
+        flags |= FontFlags::FIXED_PITCH;
+    }
+    pdf.font_descriptor(descriptor_id)
+        .name(name)
+        .flags(flags)
+        .bbox(PdfRect::new(
+            font.to_thousandths(bbox.x_min as f32),
+            font.to_thousandths(bbox.y_min as f32),
+            font.to_thousandths(bbox.x_max as f32),
+            font.to_thousandths(bbox.y_max as f32),
+        ))
+        .italic_angle(0.0)
+        .ascent(font.to_thousandths(face.ascender() as f32))
+        .descent(font.to_thousandths(face.descender() as f32))
+        .cap_height(font.to_thousandths(face.capital_height().unwrap_or(0) as f32))
+        .stem_v(80.0)
+        .font_file2(file_id);
+
+    let mut cmap = UnicodeCmap::new(Name(b"Custom"), SYSTEM_INFO);
+    for (glyph, c) in &font.used {
+        cmap.pair(*glyph, *c);
+    }
+    pdf.cmap(cmap_id, &cmap.finish());
+
+    let data = face.raw_face().data;
+    pdf.stream(file_id, data)
+        .pair(Name(b"Length1"), data.len() as i32);
+
+    pdf.finish()
+}
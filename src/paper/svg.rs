@@ -0,0 +1,577 @@
+//! Writing a [`Paper`] as an SVG document.
+
+use std::borrow::Cow;
+use std::fmt::Write as _;
+use std::hash::Hasher as _;
+
+use xmlwriter::XmlWriter;
+
+use crate::source::{NAMESPACE, VERSION};
+
+use super::{
+    Backend, Fill, Group, Marker, Paper, Path, Point, Rect, Segment, Stroke, Text, TextAnchor,
+};
+
+const MARKERS: &[(Marker, &str)] = &[
+    (Marker::ArrowBlock, "arrowblock"),
+    (Marker::ArrowOpen, "arrowopen"),
+];
+
+/// `text` for a text node or attribute value; `xmlwriter` escapes `<` in
+/// text and quotes in attributes, but not `&` or the `>` of a `]]>`.
+fn escape(text: &str) -> Cow<'_, str> {
+    if text.contains(['&', '<', '>']) {
+        let text = text.replace('&', "&amp;");
+        Cow::Owned(text.replace('<', "&lt;").replace('>', "&gt;"))
+    } else {
+        Cow::Borrowed(text)
+    }
+}
+
+/// `seqdia-` and a hash of what is drawn, so that only identical diagrams,
+/// whose definitions are the same anyway, share ids.
+fn default_id_prefix(paper: &Paper) -> String {
+    let hash = paper.render(ElementHasher::default());
+    format!("seqdia-{:08x}", hash as u32)
+}
+
+/// Hashes the elements of a paper, numbers by their bits, so that the hash
+/// only depends on what is drawn.
+#[derive(Default)]
+struct ElementHasher(fnv::FnvHasher);
+
+impl ElementHasher {
+    fn number(&mut self, number: f32) {
+        self.0.write_u32(number.to_bits());
+    }
+
+    fn str(&mut self, text: &str) {
+        self.0.write_usize(text.len());
+        self.0.write(text.as_bytes());
+    }
+
+    fn option_str(&mut self, text: Option<&str>) {
+        self.0.write_u8(text.is_some() as u8);
+        self.str(text.unwrap_or_default());
+    }
+
+    fn point(&mut self, point: Point) {
+        self.number(point.x);
+        self.number(point.y);
+    }
+
+    fn marker(&mut self, marker: Option<Marker>) {
+        self.0.write_u8(match marker {
+            None => 0,
+            Some(Marker::ArrowBlock) => 1,
+            Some(Marker::ArrowOpen) => 2,
+        });
+    }
+
+    fn stroke(&mut self, stroke: Option<&Stroke>) {
+        self.0.write_u8(stroke.is_some() as u8);
+        if let Some(stroke) = stroke {
+            self.str(&stroke.color);
+            self.number(stroke.width);
+            self.0.write_usize(stroke.dash.len());
+            stroke.dash.iter().for_each(|&dash| self.number(dash));
+        }
+    }
+}
+
+impl Backend for ElementHasher {
+    type Output = u64;
+
+    fn begin(&mut self, paper: &Paper) {
+        self.number(paper.width);
+        self.number(paper.height);
+        self.option_str(paper.title.as_deref());
+        self.str(&paper.description);
+        self.option_str(paper.theme.background.as_deref());
+    }
+
+    fn begin_group(&mut self, group: &Group) {
+        self.0.write_u8(b'g');
+        self.option_str(group.class.as_deref());
+        self.option_str(group.id.as_deref());
+        self.option_str(group.label.as_deref());
+        self.0.write_usize(group.data.len());
+        for (name, value) in &group.data {
+            self.str(name);
+            self.str(value);
+        }
+    }
+
+    fn end_group(&mut self, _group: &Group) {
+        self.0.write_u8(b'/');
+    }
+
+    fn path(&mut self, path: &Path) {
+        self.0.write_u8(b'p');
+        for segment in &path.segments {
+            match *segment {
+                Segment::MoveTo(p) => {
+                    self.0.write_u8(b'M');
+                    self.point(p);
+                }
+                Segment::LineTo(p) => {
+                    self.0.write_u8(b'L');
+                    self.point(p);
+                }
+                Segment::CubicTo(c1, c2, p) => {
+                    self.0.write_u8(b'C');
+                    self.point(c1);
+                    self.point(c2);
+                    self.point(p);
+                }
+                Segment::Close => self.0.write_u8(b'Z'),
+            }
+        }
+        self.stroke(Some(&path.stroke));
+        self.marker(path.marker_start);
+        self.marker(path.marker_end);
+    }
+
+    fn rect(&mut self, rect: &Rect) {
+        self.0.write_u8(b'r');
+        self.number(rect.x);
+        self.number(rect.y);
+        self.number(rect.width);
+        self.number(rect.height);
+        self.number(rect.radius);
+        self.0.write_u8(rect.fill.is_some() as u8);
+        if let Some(fill) = &rect.fill {
+            self.str(&fill.color);
+            self.number(fill.opacity);
+        }
+        self.stroke(rect.stroke.as_ref());
+    }
+
+    fn text(&mut self, text: &Text) {
+        self.0.write_u8(b't');
+        self.number(text.x);
+        self.number(text.y);
+        self.0.write_usize(text.lines.len());
+        text.lines.iter().for_each(|line| self.str(line));
+        self.0.write_u8(text.anchor as u8);
+        let style = &text.style;
+        self.option_str(style.font_family.as_deref());
+        self.number(style.font_size);
+        self.str(&style.color);
+        self.number(style.ascent);
+        self.number(style.line_height);
+    }
+
+    fn finish(self) -> u64 {
+        self.0.finish()
+    }
+}
+
+/// Write `text` into the element just started and end it, without the
+/// indentation `writer` would add to the text.
+fn write_text_as_is(writer: &mut XmlWriter, text: &str) {
+    writer.set_preserve_whitespaces(true);
+    writer.write_text(&escape(text));
+    writer.end_element();
+    writer.set_preserve_whitespaces(false);
+}
+
+/// How large an SVG is shown. Its `viewBox` is always the whole paper, so
+/// it scales cleanly to any size.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[non_exhaustive]
+pub enum SvgSize {
+    /// the size of the paper, times the zoom
+    #[default]
+    Intrinsic,
+    /// as wide as its container, and as high as that keeps the proportions
+    FitWidth,
+    /// `width` by `height` pixels, the diagram scaled to fit and centred
+    Fixed(f32, f32),
+}
+
+/// Draws a [`Paper`] as an SVG document.
+///
+/// Ids in it start with a prefix, so that several SVGs inlined into one
+/// HTML page do not refer to each other's definitions.
+///
+/// Screen readers get the paper's title and description, then each
+/// labelled group in drawing order, the text drawn in it being hidden.
+pub struct SvgBackend {
+    writer: XmlWriter,
+    source: Option<String>,
+    id_prefix: Option<String>,
+    size: SvgSize,
+    zoom: f32,
+    /// how many labelled groups the current element is in
+    labelled: usize,
+}
+
+impl Default for SvgBackend {
+    fn default() -> Self {
+        Self {
+            writer: XmlWriter::new(Default::default()),
+            source: None,
+            id_prefix: None,
+            size: SvgSize::default(),
+            zoom: 1.0,
+            labelled: 0,
+        }
+    }
+}
+
+impl SvgBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Keep the text the diagram was drawn from in the SVG `<metadata>`, to
+    /// be read back with [`source_from_svg`](crate::source_from_svg).
+    pub fn with_source(self, source: impl Into<String>) -> Self {
+        let source = Some(source.into());
+        Self { source, ..self }
+    }
+
+    /// Start ids with `prefix` rather than one derived from the diagram. It
+    /// must be a valid XML name, such as `diagram1`.
+    pub fn with_id_prefix(self, prefix: impl Into<String>) -> Self {
+        let id_prefix = Some(prefix.into());
+        Self { id_prefix, ..self }
+    }
+
+    pub fn with_size(self, size: SvgSize) -> Self {
+        Self { size, ..self }
+    }
+
+    /// Show an [`SvgSize::Intrinsic`] SVG `zoom` times as large.
+    pub fn with_zoom(self, zoom: f32) -> Self {
+        Self { zoom, ..self }
+    }
+
+    fn id(&self, name: &str) -> String {
+        format!("{}-{}", self.id_prefix.as_deref().unwrap_or_default(), name)
+    }
+
+    fn metadata(&mut self, source: &str) {
+        let writer = &mut self.writer;
+        writer.start_element("metadata");
+        writer.start_element("seqdia:source");
+        writer.write_attribute("xmlns:seqdia", NAMESPACE);
+        writer.write_attribute("version", VERSION);
+        write_text_as_is(writer, source);
+        writer.end_element();
+    }
+
+    fn marker(&mut self, marker: Marker, name: &str, color: &str) {
+        let id = self.id(name);
+        let (view_width, view_height) = marker.view_box();
+        let (width, height) = marker.size();
+        let reference = marker.reference();
+        let mut d = String::new();
+        for (i, (x, y)) in marker.outline().iter().enumerate() {
+            let command = if i == 0 { 'M' } else { 'L' };
+            write!(d, "{}{},{} ", command, x, y).unwrap();
+        }
+        d.push('z');
+
+        let writer = &mut self.writer;
+        writer.start_element("marker");
+        writer.write_attribute("id", &id);
+        writer.write_attribute_fmt(
+            "viewBox",
+            format_args!("0 0 {} {}", view_width, view_height),
+        );
+        writer.write_attribute_fmt("markerWidth", format_args!("{}", width));
+        writer.write_attribute_fmt("markerHeight", format_args!("{}", height));
+        // the same marker points back at the start of a path
+        writer.write_attribute("orient", "auto-start-reverse");
+        writer.write_attribute_fmt("refX", format_args!("{}", reference.x));
+        writer.write_attribute_fmt("refY", format_args!("{}", reference.y));
+        writer.start_element("path");
+        writer.write_attribute("d", &d);
+        writer.write_attribute("fill", color);
+        writer.end_element();
+        writer.end_element();
+    }
+
+    fn fill(&mut self, fill: Option<&Fill>) {
+        match fill {
+            Some(fill) => {
+                self.writer.write_attribute("fill", &fill.color);
+                if fill.opacity < 1.0 {
+                    self.writer
+                        .write_attribute_fmt("fill-opacity", format_args!("{}", fill.opacity));
+                }
+            }
+            None => self.writer.write_attribute("fill", "none"),
+        }
+    }
+
+    fn stroke(&mut self, stroke: Option<&Stroke>) {
+        let stroke = match stroke {
+            Some(stroke) => stroke,
+            None => {
+                self.writer.write_attribute("stroke", "none");
+                return;
+            }
+        };
+        self.writer.write_attribute("stroke", &stroke.color);
+        self.writer
+            .write_attribute_fmt("stroke-width", format_args!("{}px", stroke.width));
+        if !stroke.dash.is_empty() {
+            let dash = stroke
+                .dash
+                .iter()
+                .map(|length| format!("{}px", length))
+                .collect::<Vec<_>>();
+            self.writer
+                .write_attribute("stroke-dasharray", &dash.join(","));
+        }
+    }
+}
+
+impl Backend for SvgBackend {
+    type Output = String;
+
+    fn begin(&mut self, paper: &Paper) {
+        if self.id_prefix.is_none() {
+            self.id_prefix = Some(default_id_prefix(paper));
+        }
+
+        let title_id = self.id("title");
+        let desc_id = self.id("desc");
+        let writer = &mut self.writer;
+        writer.start_element("svg");
+        writer.write_attribute("xmlns", "http://www.w3.org/2000/svg");
+        match self.size {
+            SvgSize::Intrinsic => {
+                writer.write_attribute_fmt("width", format_args!("{}", paper.width * self.zoom));
+                writer.write_attribute_fmt("height", format_args!("{}", paper.height * self.zoom));
+            }
+            SvgSize::FitWidth => writer.write_attribute("width", "100%"),
+            SvgSize::Fixed(width, height) => {
+                writer.write_attribute_fmt("width", format_args!("{}", width));
+                writer.write_attribute_fmt("height", format_args!("{}", height));
+            }
+        }
+        writer.write_attribute_fmt(
+            "viewBox",
+            format_args!("0 0 {} {}", paper.width, paper.height),
+        );
+        writer.write_attribute_fmt("version", format_args!("{}", 1.1));
+        writer.write_attribute("role", "graphics-document document");
+        match paper.title {
+            Some(_) => writer.write_attribute("aria-labelledby", &title_id),
+            None => writer.write_attribute("aria-label", "Sequence diagram"),
+        }
+        writer.write_attribute("aria-describedby", &desc_id);
+
+        if let Some(title) = &paper.title {
+            writer.start_element("title");
+            writer.write_attribute("id", &title_id);
+            write_text_as_is(writer, title);
+        }
+        writer.start_element("desc");
+        writer.write_attribute("id", &desc_id);
+        write_text_as_is(writer, &paper.description);
+
+        if let Some(source) = self.source.take() {
+            self.metadata(&source);
+        }
+
+        self.writer.start_element("defs");
+        for (marker, id) in MARKERS {
+            self.marker(*marker, id, &paper.theme.line_color);
+        }
+        self.writer.end_element();
+
+        if let Some(background) = &paper.theme.background {
+            self.rect(
+                &Rect::new(0.0, 0.0, paper.width, paper.height)
+                    .with_fill(Fill::new(background.as_str())),
+            );
+        }
+    }
+
+    fn begin_group(&mut self, group: &Group) {
+        let id = group.id.as_deref().map(|id| self.id(id));
+        let writer = &mut self.writer;
+        writer.start_element("g");
+        if let Some(id) = id {
+            writer.write_attribute("id", &id);
+        }
+        if let Some(class) = &group.class {
+            writer.write_attribute("class", class);
+        }
+        if let Some(label) = &group.label {
+            writer.write_attribute("role", "group");
+            writer.write_attribute("aria-label", &escape(label));
+            self.labelled += 1;
+        }
+        for (name, value) in &group.data {
+            writer.write_attribute(&format!("data-{}", name), &escape(value));
+        }
+    }
+
+    fn end_group(&mut self, group: &Group) {
+        if group.label.is_some() {
+            self.labelled -= 1;
+        }
+        self.writer.end_element();
+    }
+
+    fn path(&mut self, path: &Path) {
+        let mut d = String::new();
+        for segment in &path.segments {
+            if !d.is_empty() {
+                d.push(' ');
+            }
+            match segment {
+                Segment::MoveTo(p) => write!(d, "M{},{}", p.x, p.y),
+                Segment::LineTo(p) => write!(d, "L{},{}", p.x, p.y),
+                Segment::CubicTo(c1, c2, p) => {
+                    write!(d, "C{},{} {},{} {},{}", c1.x, c1.y, c2.x, c2.y, p.x, p.y)
+                }
+                Segment::Close => write!(d, "z"),
+            }
+            .unwrap();
+        }
+
+        self.writer.start_element("path");
+        self.fill(None);
+        self.stroke(Some(&path.stroke));
+        self.writer.write_attribute("d", &d);
+        for (attribute, marker) in &[
+            ("marker-start", path.marker_start),
+            ("marker-end", path.marker_end),
+        ] {
+            if let Some(marker) = marker {
+                let (_, name) = MARKERS.iter().find(|(m, _)| m == marker).unwrap();
+                let id = self.id(name);
+                self.writer
+                    .write_attribute_fmt(attribute, format_args!("url(#{})", id));
+            }
+        }
+        self.writer.end_element();
+    }
+
+    fn rect(&mut self, rect: &Rect) {
+        let writer = &mut self.writer;
+        writer.start_element("rect");
+        writer.write_attribute_fmt("x", format_args!("{}", rect.x));
+        writer.write_attribute_fmt("y", format_args!("{}", rect.y));
+        writer.write_attribute_fmt("width", format_args!("{}", rect.width));
+        writer.write_attribute_fmt("height", format_args!("{}", rect.height));
+        if rect.radius > 0.0 {
+            writer.write_attribute_fmt("rx", format_args!("{}", rect.radius));
+            writer.write_attribute_fmt("ry", format_args!("{}", rect.radius));
+        }
+        self.fill(rect.fill.as_ref());
+        self.stroke(rect.stroke.as_ref());
+        self.writer.end_element();
+    }
+
+    fn text(&mut self, text: &Text) {
+        let writer = &mut self.writer;
+        let style = &text.style;
+        writer.start_element("text");
+        writer.write_attribute_fmt("x", format_args!("{}", text.x));
+        writer.write_attribute_fmt("y", format_args!("{}", text.y));
+        writer.write_attribute(
+            "text-anchor",
+            match text.anchor {
+                TextAnchor::Start => "start",
+                TextAnchor::Middle => "middle",
+                TextAnchor::End => "end",
+            },
+        );
+        writer.write_attribute_fmt("font-size", format_args!("{}px", style.font_size));
+        if let Some(font_family) = &style.font_family {
+            writer.write_attribute("font-family", font_family);
+        }
+        writer.write_attribute("fill", &style.color);
+        if self.labelled > 0 {
+            // read out as the label of the group
+            writer.write_attribute("aria-hidden", "true");
+        }
+
+        for (i, line) in text.lines.iter().enumerate() {
+            let dy = if i == 0 {
+                style.ascent
+            } else {
+                style.line_height
+            };
+            writer.start_element("tspan");
+            writer.write_attribute_fmt("dy", format_args!("{}", dy));
+            writer.write_attribute_fmt("x", format_args!("{}", text.x));
+            writer.write_text(&escape(line));
+            writer.end_element();
+        }
+
+        writer.end_element();
+    }
+
+    fn finish(self) -> String {
+        self.writer.end_document()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{draw, parse, RenderOptions};
+
+    fn paper() -> Paper {
+        draw(&parse("A -> B: hi").unwrap(), &RenderOptions::default())
+    }
+
+    /// The `<svg>` start tag of `paper` drawn by `backend`.
+    fn root(paper: &Paper, backend: SvgBackend) -> String {
+        let svg = paper.render(backend);
+        svg[..svg.find('>').unwrap()].to_string()
+    }
+
+    #[test]
+    fn view_box_is_the_paper() {
+        let paper = paper();
+        let view_box = format!(r#"viewBox="0 0 {} {}""#, paper.width, paper.height);
+        for size in [
+            SvgSize::Intrinsic,
+            SvgSize::FitWidth,
+            SvgSize::Fixed(300.0, 200.0),
+        ] {
+            let backend = SvgBackend::new().with_size(size).with_zoom(2.0);
+            assert!(root(&paper, backend).contains(&view_box), "{:?}", size);
+        }
+    }
+
+    #[test]
+    fn intrinsic_size_times_the_zoom() {
+        let paper = paper();
+        let size = format!(r#"width="{}" height="{}""#, paper.width, paper.height);
+        assert!(root(&paper, SvgBackend::new()).contains(&size));
+        let size = format!(
+            r#"width="{}" height="{}""#,
+            paper.width * 2.0,
+            paper.height * 2.0
+        );
+        assert!(root(&paper, SvgBackend::new().with_zoom(2.0)).contains(&size));
+    }
+
+    #[test]
+    fn fit_width_leaves_the_height_to_the_proportions() {
+        let paper = paper();
+        let root = root(&paper, SvgBackend::new().with_size(SvgSize::FitWidth));
+        assert!(root.contains(r#"width="100%""#));
+        assert!(!root.contains("height="));
+    }
+
+    #[test]
+    fn fixed_size_ignores_the_zoom() {
+        let paper = paper();
+        let backend = SvgBackend::new()
+            .with_size(SvgSize::Fixed(300.0, 200.0))
+            .with_zoom(2.0);
+        assert!(root(&paper, backend).contains(r#"width="300" height="200""#));
+    }
+}
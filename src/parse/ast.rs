@@ -1,14 +1,83 @@
+use std::borrow::Cow;
 use std::iter::IntoIterator;
 use std::iter::Iterator;
 use std::slice;
 
 use super::visitor::Visitor;
 
+/// Where a node comes from in the source text: a range of byte offsets and
+/// the 1-based line and column (counted in characters) of both ends.
+///
+/// Nodes built by hand rather than parsed use `Span::default()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Span {
+    start: usize,
+    end: usize,
+    start_line_col: (usize, usize),
+    end_line_col: (usize, usize),
+}
+
+impl Span {
+    pub fn new(
+        start: usize,
+        end: usize,
+        start_line_col: (usize, usize),
+        end_line_col: (usize, usize),
+    ) -> Span {
+        Span {
+            start,
+            end,
+            start_line_col,
+            end_line_col,
+        }
+    }
+
+    /// Byte offset of the first character.
+    pub fn start(&self) -> usize {
+        self.start
+    }
+
+    /// Byte offset just past the last character.
+    pub fn end(&self) -> usize {
+        self.end
+    }
+
+    pub fn start_line_col(&self) -> (usize, usize) {
+        self.start_line_col
+    }
+
+    pub fn end_line_col(&self) -> (usize, usize) {
+        self.end_line_col
+    }
+
+    /// The smallest span covering both `self` and `other`.
+    pub fn to(&self, other: &Span) -> Span {
+        let (start, start_line_col) = if self.start <= other.start {
+            (self.start, self.start_line_col)
+        } else {
+            (other.start, other.start_line_col)
+        };
+        let (end, end_line_col) = if self.end >= other.end {
+            (self.end, self.end_line_col)
+        } else {
+            (other.end, other.end_line_col)
+        };
+        Span::new(start, end, start_line_col, end_line_col)
+    }
+}
+
+/// A whole diagram: its statements in source order.
+///
+/// Text is borrowed from the parsed source for `'i`. [`Document::into_owned`]
+/// copies it out, giving an [`OwnedDocument`].
 #[derive(Debug, Clone)]
-pub(crate) struct Document<'i>(Vec<Statement<'i>>);
+pub struct Document<'i>(Vec<Statement<'i>>);
+
+/// A document that does not borrow from its source text.
+pub type OwnedDocument = Document<'static>;
 
 impl<'i> Document<'i> {
-    pub(crate) fn accept<V, C, O>(&self, visitor: &V, cx: &mut C) -> O
+    pub fn accept<V, C, O>(&self, visitor: &V, cx: &mut C) -> O
     where
         V: Visitor<'i, Context = C, Output = O>,
     {
@@ -18,6 +87,10 @@ impl<'i> Document<'i> {
     pub fn iter<'s>(&'s self) -> DocumentIter<'i, 's> {
         DocumentIter(self.0.iter())
     }
+
+    pub fn into_owned(self) -> OwnedDocument {
+        Document(self.0.into_iter().map(Statement::into_owned).collect())
+    }
 }
 
 impl<'i, 's> IntoIterator for &'s Document<'i> {
@@ -29,7 +102,7 @@ impl<'i, 's> IntoIterator for &'s Document<'i> {
 }
 
 #[derive(Debug)]
-pub(crate) struct DocumentIter<'i, 's>(slice::Iter<'s, Statement<'i>>);
+pub struct DocumentIter<'i, 's>(slice::Iter<'s, Statement<'i>>);
 
 impl<'i, 's> Iterator for DocumentIter<'i, 's> {
     type Item = &'s Statement<'i>;
@@ -39,26 +112,64 @@ impl<'i, 's> Iterator for DocumentIter<'i, 's> {
 }
 
 impl<'i> From<Vec<Statement<'i>>> for Document<'i> {
-    fn from(v: Vec<Statement<'i>>) -> Document {
+    fn from(v: Vec<Statement<'i>>) -> Document<'i> {
         Document(v)
     }
 }
 
+/// One line of a diagram, or a whole fragment.
 #[derive(Debug, Clone)]
-pub(crate) enum Statement<'i> {
+#[non_exhaustive]
+pub enum Statement<'i> {
     Title(Title<'i>),
     Signal(Signal<'i>),
     Participant(Participant<'i>),
     Note(Note<'i>),
+    Activate(Activate<'i>),
+    Deactivate(Deactivate<'i>),
+    Fragment(Fragment<'i>),
+    Autonumber(Autonumber<'i>),
+    Style(Style),
+    Theme(ThemeDirective<'i>),
 }
 
 impl<'i> Statement<'i> {
-    pub(crate) fn accept<V, C, O>(&self, visitor: &V, cx: &mut C) -> O
+    pub fn accept<V, C, O>(&self, visitor: &V, cx: &mut C) -> O
     where
         V: Visitor<'i, Context = C, Output = O>,
     {
         visitor.visit_statement(self, cx)
     }
+
+    pub fn span(&self) -> Span {
+        match self {
+            Statement::Title(v) => v.span(),
+            Statement::Signal(v) => v.span(),
+            Statement::Participant(v) => v.span(),
+            Statement::Note(v) => v.span(),
+            Statement::Activate(v) => v.span(),
+            Statement::Deactivate(v) => v.span(),
+            Statement::Fragment(v) => v.span(),
+            Statement::Autonumber(v) => v.span(),
+            Statement::Style(v) => v.span(),
+            Statement::Theme(v) => v.span(),
+        }
+    }
+
+    pub fn into_owned(self) -> Statement<'static> {
+        match self {
+            Statement::Title(v) => Statement::Title(v.into_owned()),
+            Statement::Signal(v) => Statement::Signal(v.into_owned()),
+            Statement::Participant(v) => Statement::Participant(v.into_owned()),
+            Statement::Note(v) => Statement::Note(v.into_owned()),
+            Statement::Activate(v) => Statement::Activate(v.into_owned()),
+            Statement::Deactivate(v) => Statement::Deactivate(v.into_owned()),
+            Statement::Fragment(v) => Statement::Fragment(v.into_owned()),
+            Statement::Autonumber(v) => Statement::Autonumber(v.into_owned()),
+            Statement::Style(v) => Statement::Style(v),
+            Statement::Theme(v) => Statement::Theme(v.into_owned()),
+        }
+    }
 }
 
 impl<'i> From<Title<'i>> for Statement<'i> {
@@ -85,143 +196,622 @@ impl<'i> From<Note<'i>> for Statement<'i> {
     }
 }
 
+impl<'i> From<Activate<'i>> for Statement<'i> {
+    fn from(v: Activate<'i>) -> Statement<'i> {
+        Statement::Activate(v)
+    }
+}
+
+impl<'i> From<Deactivate<'i>> for Statement<'i> {
+    fn from(v: Deactivate<'i>) -> Statement<'i> {
+        Statement::Deactivate(v)
+    }
+}
+
+impl<'i> From<Fragment<'i>> for Statement<'i> {
+    fn from(v: Fragment<'i>) -> Statement<'i> {
+        Statement::Fragment(v)
+    }
+}
+
+impl<'i> From<Autonumber<'i>> for Statement<'i> {
+    fn from(v: Autonumber<'i>) -> Statement<'i> {
+        Statement::Autonumber(v)
+    }
+}
+
+impl From<Style> for Statement<'_> {
+    fn from(v: Style) -> Self {
+        Statement::Style(v)
+    }
+}
+
+impl<'i> From<ThemeDirective<'i>> for Statement<'i> {
+    fn from(v: ThemeDirective<'i>) -> Statement<'i> {
+        Statement::Theme(v)
+    }
+}
+
+/// `title text`
 #[derive(Debug, Clone)]
-pub(crate) struct Title<'i>(&'i str);
+pub struct Title<'i>(Cow<'i, str>, Span);
 
 impl<'i> Title<'i> {
-    pub(crate) fn new(v: &'i str) -> Title {
-        Title(v)
+    pub fn new(v: impl Into<Cow<'i, str>>, span: Span) -> Title<'i> {
+        Title(v.into(), span)
     }
 
-    pub(crate) fn accept<V, C, O>(&self, visitor: &V, cx: &mut C) -> O
+    pub fn span(&self) -> Span {
+        self.1
+    }
+
+    pub fn accept<V, C, O>(&self, visitor: &V, cx: &mut C) -> O
     where
         V: Visitor<'i, Context = C, Output = O>,
     {
         visitor.visit_title(self, cx)
     }
+
+    pub fn into_owned(self) -> Title<'static> {
+        Title(Cow::Owned(self.0.into_owned()), self.1)
+    }
 }
 
 impl AsRef<str> for Title<'_> {
     fn as_ref(&self) -> &str {
-        self.0
+        &self.0
     }
 }
 
+/// `from -> to: message`, or `from <- to: message` for a message the other
+/// way round
 #[derive(Debug, Clone)]
-pub(crate) struct Signal<'i>(Actor<'i>, SignalType, Actor<'i>, &'i str);
+pub struct Signal<'i>(Actor<'i>, SignalType, Actor<'i>, Cow<'i, str>, Span);
 
 impl<'i> Signal<'i> {
-    pub(crate) fn new(l: Actor<'i>, signal: SignalType, r: Actor<'i>, v: &'i str) -> Signal<'i> {
-        Signal(l, signal, r, v)
+    pub fn new(
+        l: Actor<'i>,
+        signal: SignalType,
+        r: Actor<'i>,
+        v: impl Into<Cow<'i, str>>,
+        span: Span,
+    ) -> Signal<'i> {
+        Signal(l, signal, r, v.into(), span)
     }
 
-    pub(crate) fn accept<V, C, O>(&self, visitor: &V, cx: &mut C) -> O
+    pub fn span(&self) -> Span {
+        self.4
+    }
+
+    pub fn accept<V, C, O>(&self, visitor: &V, cx: &mut C) -> O
     where
         V: Visitor<'i, Context = C, Output = O>,
     {
         visitor.visit_signal(self, cx)
     }
 
-    pub(crate) fn from(&self) -> &Actor<'i> {
+    pub fn from(&self) -> &Actor<'i> {
         &self.0
     }
 
-    pub(crate) fn to(&self) -> &Actor<'i> {
+    pub fn to(&self) -> &Actor<'i> {
         &self.2
     }
 
-    pub(crate) fn signal(&self) -> &SignalType {
+    /// Who sends the message: [`Signal::from`], unless the arrow is reversed.
+    pub fn sender(&self) -> &Actor<'i> {
+        if self.1.is_reversed() {
+            &self.2
+        } else {
+            &self.0
+        }
+    }
+
+    /// Who the message is sent to: [`Signal::to`], unless the arrow is
+    /// reversed.
+    pub fn receiver(&self) -> &Actor<'i> {
+        if self.1.is_reversed() {
+            &self.0
+        } else {
+            &self.2
+        }
+    }
+
+    pub fn signal(&self) -> &SignalType {
         &self.1
     }
 
-    pub(crate) fn message(&self) -> &str {
+    pub fn message(&self) -> &str {
         &self.3
     }
+
+    pub fn into_owned(self) -> Signal<'static> {
+        Signal(
+            self.0.into_owned(),
+            self.1,
+            self.2.into_owned(),
+            Cow::Owned(self.3.into_owned()),
+            self.4,
+        )
+    }
 }
 
+/// `participant name` or `participant name as "display name"`
 #[derive(Debug, Clone)]
-pub(crate) struct Participant<'i>(Actor<'i>, Option<Actor<'i>>);
+pub struct Participant<'i>(Actor<'i>, Option<Actor<'i>>, Span);
 
 impl<'i> Participant<'i> {
-    pub(crate) fn new(actor: Actor<'i>, alias: Option<Actor<'i>>) -> Participant<'i> {
-        Participant(actor, alias)
+    pub fn new(actor: Actor<'i>, alias: Option<Actor<'i>>, span: Span) -> Participant<'i> {
+        Participant(actor, alias, span)
+    }
+
+    pub fn span(&self) -> Span {
+        self.2
     }
 
-    pub(crate) fn display_name(&self) -> &Option<Actor<'i>> {
+    pub fn display_name(&self) -> &Option<Actor<'i>> {
         &self.1
     }
 
-    pub(crate) fn actor(&self) -> &Actor<'i> {
+    pub fn actor(&self) -> &Actor<'i> {
         &self.0
     }
 
-    pub(crate) fn accept<V, C, O>(&self, visitor: &V, cx: &mut C) -> O
+    pub fn accept<V, C, O>(&self, visitor: &V, cx: &mut C) -> O
     where
         V: Visitor<'i, Context = C, Output = O>,
     {
         visitor.visit_participant(self, cx)
     }
+
+    pub fn into_owned(self) -> Participant<'static> {
+        Participant(self.0.into_owned(), self.1.map(Actor::into_owned), self.2)
+    }
 }
 
+/// `note left of a: text`, `note right of a: text` or `note over a[, b]: text`
 #[derive(Debug, Clone)]
-pub(crate) enum Note<'i> {
-    LeftOf(Actor<'i>, &'i str),
-    RightOf(Actor<'i>, &'i str),
-    Over(Actor<'i>, Option<Actor<'i>>, &'i str),
+#[non_exhaustive]
+pub enum Note<'i> {
+    LeftOf(Actor<'i>, Cow<'i, str>, Span),
+    RightOf(Actor<'i>, Cow<'i, str>, Span),
+    Over(Actor<'i>, Option<Actor<'i>>, Cow<'i, str>, Span),
 }
 
 impl<'i> Note<'i> {
-    pub(crate) fn accept<V, C, O>(&self, visitor: &V, cx: &mut C) -> O
+    pub fn accept<V, C, O>(&self, visitor: &V, cx: &mut C) -> O
     where
         V: Visitor<'i, Context = C, Output = O>,
     {
         visitor.visit_note(self, cx)
     }
+
+    pub fn actors(&self) -> Vec<&Actor<'i>> {
+        match self {
+            Note::LeftOf(actor, ..) | Note::RightOf(actor, ..) | Note::Over(actor, None, ..) => {
+                vec![actor]
+            }
+            Note::Over(actor, Some(actor2), ..) => vec![actor, actor2],
+        }
+    }
+
+    pub fn message(&self) -> &str {
+        match self {
+            Note::LeftOf(_, message, _)
+            | Note::RightOf(_, message, _)
+            | Note::Over(_, _, message, _) => message,
+        }
+    }
+
+    pub fn span(&self) -> Span {
+        match self {
+            Note::LeftOf(.., span) | Note::RightOf(.., span) | Note::Over(.., span) => *span,
+        }
+    }
+
+    pub fn into_owned(self) -> Note<'static> {
+        match self {
+            Note::LeftOf(actor, message, span) => {
+                Note::LeftOf(actor.into_owned(), Cow::Owned(message.into_owned()), span)
+            }
+            Note::RightOf(actor, message, span) => {
+                Note::RightOf(actor.into_owned(), Cow::Owned(message.into_owned()), span)
+            }
+            Note::Over(actor, actor2, message, span) => Note::Over(
+                actor.into_owned(),
+                actor2.map(Actor::into_owned),
+                Cow::Owned(message.into_owned()),
+                span,
+            ),
+        }
+    }
+}
+
+/// `activate name`
+#[derive(Debug, Clone)]
+pub struct Activate<'i>(Actor<'i>, Span);
+
+impl<'i> Activate<'i> {
+    pub fn new(actor: Actor<'i>, span: Span) -> Activate<'i> {
+        Activate(actor, span)
+    }
+
+    pub fn span(&self) -> Span {
+        self.1
+    }
+
+    pub fn actor(&self) -> &Actor<'i> {
+        &self.0
+    }
+
+    pub fn accept<V, C, O>(&self, visitor: &V, cx: &mut C) -> O
+    where
+        V: Visitor<'i, Context = C, Output = O>,
+    {
+        visitor.visit_activate(self, cx)
+    }
+
+    pub fn into_owned(self) -> Activate<'static> {
+        Activate(self.0.into_owned(), self.1)
+    }
+}
+
+/// `deactivate name`
+#[derive(Debug, Clone)]
+pub struct Deactivate<'i>(Actor<'i>, Span);
+
+impl<'i> Deactivate<'i> {
+    pub fn new(actor: Actor<'i>, span: Span) -> Deactivate<'i> {
+        Deactivate(actor, span)
+    }
+
+    pub fn span(&self) -> Span {
+        self.1
+    }
+
+    pub fn actor(&self) -> &Actor<'i> {
+        &self.0
+    }
+
+    pub fn accept<V, C, O>(&self, visitor: &V, cx: &mut C) -> O
+    where
+        V: Visitor<'i, Context = C, Output = O>,
+    {
+        visitor.visit_deactivate(self, cx)
+    }
+
+    pub fn into_owned(self) -> Deactivate<'static> {
+        Deactivate(self.0.into_owned(), self.1)
+    }
+}
+
+/// `autonumber [start [step]] ["format"]` or `autonumber off`.
+#[derive(Debug, Clone)]
+pub enum Autonumber<'i> {
+    On(Option<usize>, Option<usize>, Option<Cow<'i, str>>, Span),
+    Off(Span),
+}
+
+impl<'i> Autonumber<'i> {
+    pub fn span(&self) -> Span {
+        match self {
+            Autonumber::On(.., span) | Autonumber::Off(span) => *span,
+        }
+    }
+
+    pub fn accept<V, C, O>(&self, visitor: &V, cx: &mut C) -> O
+    where
+        V: Visitor<'i, Context = C, Output = O>,
+    {
+        visitor.visit_autonumber(self, cx)
+    }
+
+    pub fn into_owned(self) -> Autonumber<'static> {
+        match self {
+            Autonumber::On(start, step, format, span) => Autonumber::On(
+                start,
+                step,
+                format.map(|format| Cow::Owned(format.into_owned())),
+                span,
+            ),
+            Autonumber::Off(span) => Autonumber::Off(span),
+        }
+    }
+}
+
+/// `style clean`: how boxes and lines are drawn.
+#[derive(Debug, Clone)]
+pub struct Style(StyleKind, Span);
+
+impl Style {
+    pub fn new(kind: StyleKind, span: Span) -> Style {
+        Style(kind, span)
+    }
+
+    pub fn span(&self) -> Span {
+        self.1
+    }
+
+    pub fn kind(&self) -> &StyleKind {
+        &self.0
+    }
+
+    pub fn accept<'i, V, C, O>(&self, visitor: &V, cx: &mut C) -> O
+    where
+        V: Visitor<'i, Context = C, Output = O>,
+    {
+        visitor.visit_style(self, cx)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum StyleKind {
+    /// Every line a little off, as if drawn by hand.
+    #[default]
+    HandDrawn,
+    /// Exact straight lines and square corners.
+    Clean,
+    /// Straight lines and boxes with rounded corners.
+    Rounded,
+}
+
+impl AsRef<str> for StyleKind {
+    fn as_ref(&self) -> &str {
+        match self {
+            StyleKind::HandDrawn => "handdrawn",
+            StyleKind::Clean => "clean",
+            StyleKind::Rounded => "rounded",
+        }
+    }
+}
+
+/// `theme <name>` picks a built-in theme; `theme <property> <value>` changes
+/// one property of the current one.
+#[derive(Debug, Clone)]
+pub enum ThemeDirective<'i> {
+    Named(Cow<'i, str>, Span),
+    Set(Cow<'i, str>, Cow<'i, str>, Span),
+}
+
+impl<'i> ThemeDirective<'i> {
+    pub fn span(&self) -> Span {
+        match self {
+            ThemeDirective::Named(.., span) | ThemeDirective::Set(.., span) => *span,
+        }
+    }
+
+    pub fn accept<V, C, O>(&self, visitor: &V, cx: &mut C) -> O
+    where
+        V: Visitor<'i, Context = C, Output = O>,
+    {
+        visitor.visit_theme(self, cx)
+    }
+
+    pub fn into_owned(self) -> ThemeDirective<'static> {
+        match self {
+            ThemeDirective::Named(name, span) => {
+                ThemeDirective::Named(Cow::Owned(name.into_owned()), span)
+            }
+            ThemeDirective::Set(property, value, span) => ThemeDirective::Set(
+                Cow::Owned(property.into_owned()),
+                Cow::Owned(value.into_owned()),
+                span,
+            ),
+        }
+    }
+}
+
+/// A combined fragment such as `alt` or `loop`, made of one section per
+/// `else` branch.
+#[derive(Debug, Clone)]
+pub struct Fragment<'i>(FragmentKind, Vec<Section<'i>>, Span);
+
+impl<'i> Fragment<'i> {
+    pub fn new(kind: FragmentKind, sections: Vec<Section<'i>>, span: Span) -> Fragment<'i> {
+        Fragment(kind, sections, span)
+    }
+
+    pub fn span(&self) -> Span {
+        self.2
+    }
+
+    pub fn kind(&self) -> &FragmentKind {
+        &self.0
+    }
+
+    pub fn sections(&self) -> &[Section<'i>] {
+        &self.1
+    }
+
+    pub fn accept<V, C, O>(&self, visitor: &V, cx: &mut C) -> O
+    where
+        V: Visitor<'i, Context = C, Output = O>,
+    {
+        visitor.visit_fragment(self, cx)
+    }
+
+    pub fn into_owned(self) -> Fragment<'static> {
+        Fragment(
+            self.0,
+            self.1.into_iter().map(Section::into_owned).collect(),
+            self.2,
+        )
+    }
+}
+
+/// One branch of a fragment, from its header or `else` line up to the next
+/// `else` or `end`.
+#[derive(Debug, Clone)]
+pub struct Section<'i>(Option<Cow<'i, str>>, Vec<Statement<'i>>, Span);
+
+impl<'i> Section<'i> {
+    pub fn new(
+        guard: Option<impl Into<Cow<'i, str>>>,
+        statements: Vec<Statement<'i>>,
+        span: Span,
+    ) -> Section<'i> {
+        Section(guard.map(Into::into), statements, span)
+    }
+
+    pub fn span(&self) -> Span {
+        self.2
+    }
+
+    pub(crate) fn extend_span(&mut self, span: &Span) {
+        self.2 = self.2.to(span)
+    }
+
+    pub fn guard(&self) -> Option<&str> {
+        self.0.as_deref()
+    }
+
+    pub fn statements(&self) -> &[Statement<'i>] {
+        &self.1
+    }
+
+    pub(crate) fn push(&mut self, statement: Statement<'i>) {
+        self.1.push(statement)
+    }
+
+    pub fn into_owned(self) -> Section<'static> {
+        Section(
+            self.0.map(|guard| Cow::Owned(guard.into_owned())),
+            self.1.into_iter().map(Statement::into_owned).collect(),
+            self.2,
+        )
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub(crate) struct Actor<'i>(&'i str);
+#[non_exhaustive]
+pub enum FragmentKind {
+    Alt,
+    Opt,
+    Loop,
+    Par,
+    Critical,
+    Break,
+}
 
-impl Actor<'_> {
-    pub(crate) fn new(v: &str) -> Actor {
-        Actor(v)
+impl AsRef<str> for FragmentKind {
+    fn as_ref(&self) -> &str {
+        match self {
+            FragmentKind::Alt => "alt",
+            FragmentKind::Opt => "opt",
+            FragmentKind::Loop => "loop",
+            FragmentKind::Par => "par",
+            FragmentKind::Critical => "critical",
+            FragmentKind::Break => "break",
+        }
     }
 }
 
-impl<'i> AsRef<str> for Actor<'i> {
+/// Actors are told apart by name only; the span is where this mention is.
+#[derive(Debug, Clone)]
+pub struct Actor<'i>(Cow<'i, str>, Span);
+
+impl<'i> Actor<'i> {
+    pub fn new(v: impl Into<Cow<'i, str>>, span: Span) -> Actor<'i> {
+        Actor(v.into(), span)
+    }
+
+    pub fn span(&self) -> Span {
+        self.1
+    }
+
+    pub fn into_owned(self) -> Actor<'static> {
+        Actor(Cow::Owned(self.0.into_owned()), self.1)
+    }
+}
+
+impl PartialEq for Actor<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Eq for Actor<'_> {}
+
+impl AsRef<str> for Actor<'_> {
     fn as_ref(&self) -> &str {
         &self.0
     }
 }
 
+/// The arrow of a signal, such as `->>`, `<-`, `<-->` or `-->+`.
 #[derive(Debug, Clone)]
-pub(crate) struct SignalType(LineType, ArrowType);
+pub struct SignalType(LineType, ArrowType, ArrowType, Option<Activation>, Span);
+
+impl From<(LineType, ArrowType, Span)> for SignalType {
+    fn from(v: (LineType, ArrowType, Span)) -> SignalType {
+        SignalType(v.0, ArrowType::None, v.1, None, v.2)
+    }
+}
+
+impl From<(LineType, ArrowType, Option<Activation>, Span)> for SignalType {
+    fn from(v: (LineType, ArrowType, Option<Activation>, Span)) -> SignalType {
+        SignalType(v.0, ArrowType::None, v.1, v.2, v.3)
+    }
+}
 
-impl From<(LineType, ArrowType)> for SignalType {
-    fn from(v: (LineType, ArrowType)) -> SignalType {
-        SignalType(v.0, v.1)
+/// (line, head at `from`, head at `to`, activation, span)
+impl From<(LineType, ArrowType, ArrowType, Option<Activation>, Span)> for SignalType {
+    fn from(v: (LineType, ArrowType, ArrowType, Option<Activation>, Span)) -> SignalType {
+        SignalType(v.0, v.1, v.2, v.3, v.4)
     }
 }
 
 impl SignalType {
-    pub(crate) fn arrow_type(&self) -> ArrowType {
+    pub fn span(&self) -> Span {
+        self.4
+    }
+
+    /// `+` activates the receiver, `-` deactivates the sender.
+    pub fn activation(&self) -> Option<Activation> {
+        self.3.clone()
+    }
+
+    /// The head at the end of the arrow, next to the actor on its right in
+    /// the text.
+    pub fn arrow_type(&self) -> ArrowType {
+        self.2.clone()
+    }
+
+    /// The head at the start of the arrow, next to the actor on its left in
+    /// the text, as in `<-` or `<->`.
+    pub fn start_arrow_type(&self) -> ArrowType {
         self.1.clone()
     }
 
-    pub(crate) fn line_type(&self) -> LineType {
+    /// Whether the arrow only points at its start, as in `B <- A`, so the
+    /// message goes from right to left in the text.
+    pub fn is_reversed(&self) -> bool {
+        self.1 != ArrowType::None && self.2 == ArrowType::None
+    }
+
+    pub fn line_type(&self) -> LineType {
         self.0.clone()
     }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub(crate) enum LineType {
+#[non_exhaustive]
+pub enum LineType {
     Normal,
     Dot,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub(crate) enum ArrowType {
+#[non_exhaustive]
+pub enum ArrowType {
     None,
     Normal,
     Open,
 }
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Activation {
+    Activate,
+    Deactivate,
+}
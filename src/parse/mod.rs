@@ -1,6 +1,6 @@
-pub(crate) use ast::*;
+pub use ast::*;
 pub(crate) use parse_impl::*;
-pub(crate) use visitor::*;
+pub use visitor::*;
 
 mod ast;
 mod parse_impl;
@@ -1,94 +1,255 @@
 #![allow(clippy::upper_case_acronyms)]
+use std::borrow::Cow;
+
+use pest::error::{Error, ErrorVariant, InputLocation};
 use pest::iterators::Pair;
 use pest::Parser as _;
 
 use super::ast::{
-    Actor, ArrowType, Document, LineType, Note, Participant, Signal, SignalType, Statement, Title,
+    Activate, Activation, Actor, ArrowType, Autonumber, Deactivate, Document, Fragment,
+    FragmentKind, LineType, Note, Participant, Section, Signal, SignalType, Span, Statement, Style,
+    StyleKind, ThemeDirective, Title,
 };
+use crate::{Numbering, Theme};
 
 #[derive(pest_derive::Parser)]
 #[grammar = "parse/document.pest"]
 struct MyParser;
 
-fn parse_signaltype(pair: Pair<Rule>) -> SignalType {
+/// The line being parsed: its byte offset in the whole input and its
+/// 1-based line number.
+#[derive(Debug, Clone, Copy)]
+struct Line {
+    offset: usize,
+    number: usize,
+}
+
+impl Line {
+    /// Span of `pair` in the whole input. Pairs are parsed from a single
+    /// line, so only the columns have to be looked up.
+    fn span(&self, pair: &Pair<Rule>) -> Span {
+        let span = pair.as_span();
+        let (_, start_col) = span.start_pos().line_col();
+        let (_, end_col) = span.end_pos().line_col();
+        Span::new(
+            self.offset + span.start(),
+            self.offset + span.end(),
+            (self.number, start_col),
+            (self.number, end_col),
+        )
+    }
+
+    /// Span of the whole line, `text` being the line without its line break.
+    fn span_of(&self, text: &str) -> Span {
+        Span::new(
+            self.offset,
+            self.offset + text.len(),
+            (self.number, 1),
+            (self.number, text.chars().count() + 1),
+        )
+    }
+}
+
+fn parse_signaltype(pair: Pair<Rule>, line: &Line) -> SignalType {
     if pair.as_rule() != Rule::signaltype {
         panic!()
     }
-    match pair.as_str() {
-        "-" => (LineType::Normal, ArrowType::None).into(),
-        "--" => (LineType::Dot, ArrowType::None).into(),
-        "->" => (LineType::Normal, ArrowType::Normal).into(),
-        "-->" => (LineType::Dot, ArrowType::Normal).into(),
-        "->>" => (LineType::Normal, ArrowType::Open).into(),
-        "-->>" => (LineType::Dot, ArrowType::Open).into(),
+    let span = line.span(&pair);
+    let mut inner = pair.into_inner();
+    let arrow = inner.next().unwrap().as_str();
+    let (start_arrow_type, arrow) = if let Some(arrow) = arrow.strip_prefix("<<") {
+        (ArrowType::Open, arrow)
+    } else if let Some(arrow) = arrow.strip_prefix('<') {
+        (ArrowType::Normal, arrow)
+    } else {
+        (ArrowType::None, arrow)
+    };
+    let (line_type, arrow_type) = match arrow {
+        "-" => (LineType::Normal, ArrowType::None),
+        "--" => (LineType::Dot, ArrowType::None),
+        "->" => (LineType::Normal, ArrowType::Normal),
+        "-->" => (LineType::Dot, ArrowType::Normal),
+        "->>" => (LineType::Normal, ArrowType::Open),
+        "-->>" => (LineType::Dot, ArrowType::Open),
+        _ => unreachable!(),
+    };
+    let activation = inner.next().map(|pair| match pair.as_str() {
+        "+" => Activation::Activate,
+        "-" => Activation::Deactivate,
+        _ => unreachable!(),
+    });
+    (line_type, start_arrow_type, arrow_type, activation, span).into()
+}
+
+/// `text` with `\n` turned into a line break and `\\` into a backslash.
+fn unescape(text: &str) -> Cow<'_, str> {
+    if !text.contains('\\') {
+        return Cow::Borrowed(text);
+    }
+    let mut result = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        match (c, chars.peek()) {
+            ('\\', Some('n')) => {
+                result.push('\n');
+                chars.next();
+            }
+            ('\\', Some('\\')) => {
+                result.push('\\');
+                chars.next();
+            }
+            _ => result.push(c),
+        }
+    }
+    Cow::Owned(result)
+}
+
+/// `span` covers the whole mention, quotes included.
+fn parse_actor(pair: Pair<Rule>, span: Span) -> Actor {
+    match pair.as_rule() {
+        Rule::actor | Rule::actor_quoted => parse_actor(pair.into_inner().next().unwrap(), span),
+        Rule::actor_simple | Rule::actor_quoted_inner => Actor::new(unescape(pair.as_str()), span),
+        _ => unreachable!(),
+    }
+}
+
+fn parse_placement<'i>(
+    pair: Pair<Rule>,
+    actor: Actor<'i>,
+    message: Cow<'i, str>,
+    span: Span,
+) -> Note<'i> {
+    match pair.as_rule() {
+        Rule::placement => parse_placement(pair.into_inner().next().unwrap(), actor, message, span),
+        Rule::leftof => Note::LeftOf(actor, message, span),
+        Rule::rightof => Note::RightOf(actor, message, span),
         _ => unreachable!(),
     }
 }
 
-fn parse_actor(pair: Pair<Rule>) -> Actor {
+fn parse_fragmentkind(pair: Pair<Rule>) -> FragmentKind {
     match pair.as_rule() {
-        Rule::actor | Rule::actor_quoted => parse_actor(pair.into_inner().next().unwrap()),
-        Rule::actor_simple | Rule::actor_quoted_inner => Actor::new(pair.as_str()),
+        Rule::fragmentkind => parse_fragmentkind(pair.into_inner().next().unwrap()),
+        Rule::alt => FragmentKind::Alt,
+        Rule::opt => FragmentKind::Opt,
+        Rule::loop_ => FragmentKind::Loop,
+        Rule::par => FragmentKind::Par,
+        Rule::critical => FragmentKind::Critical,
+        Rule::break_ => FragmentKind::Break,
         _ => unreachable!(),
     }
 }
 
-fn parse_placement<'i>(pair: Pair<Rule>, actor: Actor<'i>, message: &'i str) -> Note<'i> {
+fn parse_stylekind(pair: Pair<Rule>) -> StyleKind {
     match pair.as_rule() {
-        Rule::placement => parse_placement(pair.into_inner().next().unwrap(), actor, message),
-        Rule::leftof => Note::LeftOf(actor, message),
-        Rule::rightof => Note::RightOf(actor, message),
+        Rule::stylekind => parse_stylekind(pair.into_inner().next().unwrap()),
+        Rule::handdrawn => StyleKind::HandDrawn,
+        Rule::clean => StyleKind::Clean,
+        Rule::rounded => StyleKind::Rounded,
         _ => unreachable!(),
     }
 }
 
-fn parse_statement(pair: Pair<Rule>) -> Statement {
+fn parse_statement<'i>(pair: Pair<'i, Rule>, line: &Line) -> Statement<'i> {
+    let span = line.span(&pair);
+    let actor = |pair: Pair<'i, Rule>| {
+        let span = line.span(&pair);
+        parse_actor(pair, span)
+    };
     match pair.as_rule() {
         Rule::title => {
-            let title = Title::new(pair.into_inner().as_str());
+            let title = Title::new(unescape(pair.into_inner().as_str()), span);
             Statement::Title(title)
         }
 
         Rule::signal => {
             let mut inner = pair.into_inner();
-            let left = parse_actor(inner.next().unwrap());
-            let signaltype = parse_signaltype(inner.next().unwrap());
-            let right = parse_actor(inner.next().unwrap());
-            let message = inner.next().unwrap().as_str();
-            let signal = Signal::new(left, signaltype, right, message);
+            let left = actor(inner.next().unwrap());
+            let signaltype = parse_signaltype(inner.next().unwrap(), line);
+            let right = actor(inner.next().unwrap());
+            let message = unescape(inner.next().unwrap().as_str());
+            let signal = Signal::new(left, signaltype, right, message, span);
             Statement::Signal(signal)
         }
 
         Rule::participant => {
             let mut inner = pair.into_inner();
-            let actor = parse_actor(inner.next().unwrap());
+            let name = actor(inner.next().unwrap());
             let participant = if let Some(pair) = inner.next() {
-                Participant::new(actor, Some(parse_actor(pair)))
+                Participant::new(name, Some(actor(pair)), span)
             } else {
-                Participant::new(actor, None)
+                Participant::new(name, None, span)
             };
             Statement::Participant(participant)
         }
+        Rule::activate => {
+            let name = actor(pair.into_inner().next().unwrap());
+            Statement::Activate(Activate::new(name, span))
+        }
+
+        Rule::deactivate => {
+            let name = actor(pair.into_inner().next().unwrap());
+            Statement::Deactivate(Deactivate::new(name, span))
+        }
+
+        Rule::autonumber => {
+            let mut numbers = vec![];
+            let mut format = None;
+            for pair in pair.into_inner() {
+                match pair.as_rule() {
+                    Rule::autonumberoff => return Statement::Autonumber(Autonumber::Off(span)),
+                    // digits only, but may still overflow
+                    Rule::number => numbers.push(pair.as_str().parse().unwrap_or(usize::MAX)),
+                    Rule::autonumberformat => {
+                        format = Some(pair.into_inner().next().unwrap().as_str().into())
+                    }
+                    e => unreachable!("{:?}", e),
+                }
+            }
+            Statement::Autonumber(Autonumber::On(
+                numbers.first().copied(),
+                numbers.get(1).copied(),
+                format,
+                span,
+            ))
+        }
+
+        Rule::style => {
+            let kind = parse_stylekind(pair.into_inner().next().unwrap());
+            Statement::Style(Style::new(kind, span))
+        }
+
+        Rule::theme => {
+            let mut inner = pair.into_inner();
+            let word = inner.next().unwrap().as_str();
+            let theme = match inner.next() {
+                Some(value) => ThemeDirective::Set(word.into(), value.as_str().into(), span),
+                None => ThemeDirective::Named(word.into(), span),
+            };
+            Statement::Theme(theme)
+        }
+
         Rule::note => {
             let mut inner = pair.into_inner();
             let maybe_placement = inner.next().unwrap();
             let note = match maybe_placement.as_rule() {
                 Rule::placement => {
-                    let actor = parse_actor(inner.next().unwrap());
-                    let message = inner.next().unwrap().as_str();
-                    parse_placement(maybe_placement, actor, message)
+                    let name = actor(inner.next().unwrap());
+                    let message = unescape(inner.next().unwrap().as_str());
+                    parse_placement(maybe_placement, name, message, span)
                 }
                 Rule::over => {
-                    let actor = parse_actor(inner.next().unwrap());
+                    let name = actor(inner.next().unwrap());
                     let maybe_actor = inner.next().unwrap();
                     if maybe_actor.as_rule() == Rule::actor {
                         Note::Over(
-                            actor,
-                            Some(parse_actor(maybe_actor)),
-                            inner.next().unwrap().as_str(),
+                            name,
+                            Some(actor(maybe_actor)),
+                            unescape(inner.next().unwrap().as_str()),
+                            span,
                         )
                     } else {
-                        Note::Over(actor, None, maybe_actor.as_str())
+                        Note::Over(name, None, unescape(maybe_actor.as_str()), span)
                     }
                 }
                 _ => unreachable!(),
@@ -99,33 +260,387 @@ fn parse_statement(pair: Pair<Rule>) -> Statement {
     }
 }
 
-fn parse_document(pair: Pair<Rule>) -> Vec<Statement> {
-    match pair.as_rule() {
-        Rule::document => {
-            let mut result = vec![];
-            for inner in pair.into_inner() {
-                result.extend(parse_document(inner))
+/// Parse `input` line by line, so that a mistake on one line does not keep
+/// the others from being drawn. Returns every statement that could be parsed
+/// along with an error for each line that could not.
+pub(crate) fn parse(input: &str) -> (Document<'_>, Vec<Error<Rule>>) {
+    let mut document = vec![];
+    let mut errors = vec![];
+    // fragments that are not closed yet, innermost last
+    let mut open: Vec<(FragmentKind, Vec<Section>, Span)> = vec![];
+
+    let mut offset = 0;
+    let mut last = Span::default();
+    for (number, text) in input.split('\n').enumerate() {
+        let line = Line {
+            offset,
+            number: number + 1,
+        };
+        offset += text.len() + 1;
+        let text = text.strip_suffix('\r').unwrap_or(text);
+        let span = line.span_of(text);
+
+        let pair = match MyParser::parse(Rule::sourceline, text) {
+            Ok(mut pairs) => pairs.next().unwrap().into_inner().next().unwrap(),
+            Err(e) => {
+                let pos = match e.location {
+                    InputLocation::Pos(pos) | InputLocation::Span((pos, _)) => pos,
+                };
+                let pos = pest::Position::new(input, line.offset + pos).unwrap();
+                errors.push(Error::new_from_pos(e.variant, pos));
+                continue;
+            }
+        };
+        if pair.as_rule() != Rule::EOI {
+            last = span;
+            if let Some((_, sections, _)) = open.last_mut() {
+                sections.last_mut().unwrap().extend_span(&span);
             }
-            result
         }
-        Rule::statement => {
-            let mut result = vec![];
-            for inner in pair.into_inner() {
-                let statement = parse_statement(inner);
-                result.push(statement)
+
+        let statement = match pair.as_rule() {
+            Rule::statement => {
+                let statement = parse_statement(pair.into_inner().next().unwrap(), &line);
+                let error = match &statement {
+                    Statement::Theme(theme) => check_theme(theme),
+                    Statement::Autonumber(autonumber) => check_autonumber(autonumber),
+                    _ => None,
+                };
+                if let Some(message) = error {
+                    errors.push(custom_error(input, &message, statement.span()));
+                }
+                statement
+            }
+            Rule::fragmentbegin => {
+                let mut inner = pair.into_inner();
+                let kind = parse_fragmentkind(inner.next().unwrap());
+                let guard = inner.next().map(|pair| unescape(pair.as_str()));
+                open.push((kind, vec![Section::new(guard, vec![], span)], span));
+                continue;
+            }
+            Rule::fragmentelse => {
+                // after `else` itself
+                let guard = pair.into_inner().nth(1).map(|pair| unescape(pair.as_str()));
+                match open.last_mut() {
+                    Some((_, sections, _)) => sections.push(Section::new(guard, vec![], span)),
+                    None => errors.push(custom_error(input, "`else` outside of a fragment", span)),
+                }
+                continue;
             }
-            result
+            Rule::fragmentend => match open.pop() {
+                Some((kind, sections, begin)) => {
+                    Statement::Fragment(Fragment::new(kind, sections, begin.to(&span)))
+                }
+                None => {
+                    errors.push(custom_error(input, "`end` outside of a fragment", span));
+                    continue;
+                }
+            },
+            Rule::EOI => continue,
+            e => unreachable!("{:?}", e),
+        };
+
+        match open.last_mut() {
+            Some((_, sections, _)) => sections.last_mut().unwrap().push(statement),
+            None => document.push(statement),
         }
-        Rule::EOI => vec![],
-        e => unreachable!("x {:?}", e),
     }
+
+    // close what is left open, so that its contents are still drawn
+    while let Some((kind, sections, begin)) = open.pop() {
+        errors.push(custom_error(
+            input,
+            "fragment is not closed with `end`",
+            begin,
+        ));
+        let statement = Statement::Fragment(Fragment::new(kind, sections, begin.to(&last)));
+        match open.last_mut() {
+            Some((_, sections, _)) => sections.last_mut().unwrap().push(statement),
+            None => document.push(statement),
+        }
+    }
+    errors.sort_by_key(|e| match e.location {
+        InputLocation::Pos(pos) | InputLocation::Span((pos, _)) => pos,
+    });
+
+    (document.into(), errors)
 }
 
-pub(crate) fn parse(input: &str) -> Result<Document, pest::error::Error<Rule>> {
-    let r = MyParser::parse(Rule::document, input)?;
-    let mut result = vec![];
-    for pair in r {
-        result.extend(parse_document(pair));
+/// Why `theme` would be ignored, if it would.
+fn check_theme(theme: &ThemeDirective) -> Option<String> {
+    match theme {
+        ThemeDirective::Named(name, _) if Theme::named(name).is_none() => Some(format!(
+            "unknown theme `{}`, expected one of {}",
+            name,
+            Theme::NAMES.join(", ")
+        )),
+        ThemeDirective::Set(property, value, _) if !Theme::default().set(property, value) => {
+            Some(format!("cannot set theme `{}` to `{}`", property, value))
+        }
+        ThemeDirective::Set(property, value, _) => {
+            let range = Theme::size_range(property)?;
+            let size = value.parse().ok()?;
+            (!range.contains(&size)).then(|| {
+                format!(
+                    "theme `{}` must be from {} to {}, not {}",
+                    property,
+                    range.start(),
+                    range.end(),
+                    size
+                )
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Why the format of `autonumber` would be ignored, if it would.
+fn check_autonumber(autonumber: &Autonumber) -> Option<String> {
+    match autonumber {
+        Autonumber::On(_, _, Some(format), _) if Numbering::new(1, 1, format).is_none() => {
+            Some(format!("autonumber format `{}` has no digits", format))
+        }
+        _ => None,
+    }
+}
+
+fn custom_error(input: &str, message: &str, span: Span) -> Error<Rule> {
+    let variant = ErrorVariant::CustomError {
+        message: message.to_string(),
+    };
+    let span = pest::Span::new(input, span.start(), span.end()).unwrap();
+    Error::new_from_span(variant, span)
+}
+
+#[cfg(test)]
+mod tests {
+    use pest::error::LineColLocation;
+
+    use super::*;
+
+    /// Line and message of each error parsing `input`.
+    fn errors(input: &str) -> Vec<(usize, String)> {
+        parse(input)
+            .1
+            .into_iter()
+            .map(|e| {
+                let line = match e.line_col {
+                    LineColLocation::Pos((line, _)) | LineColLocation::Span((line, _), _) => line,
+                };
+                (line, e.variant.message().into_owned())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn stray_else() {
+        let input = "A -> B: a\nelse other\nA -> B: b";
+        assert_eq!(
+            errors(input),
+            [(2, "`else` outside of a fragment".to_string())]
+        );
+        assert_eq!(parse(input).0.iter().count(), 2);
+    }
+
+    #[test]
+    fn stray_end() {
+        let input = "alt x\nA -> B: a\nend\nend";
+        assert_eq!(
+            errors(input),
+            [(4, "`end` outside of a fragment".to_string())]
+        );
+        assert_eq!(parse(input).0.iter().count(), 1);
+    }
+
+    #[test]
+    fn unclosed_fragment() {
+        let input = "A -> B: a\nalt x\nA -> B: b\nelse y\nA -> B: c";
+        assert_eq!(
+            errors(input),
+            [(2, "fragment is not closed with `end`".to_string())]
+        );
+        // still drawn, with everything after it inside
+        let (document, _) = parse(input);
+        match document.iter().nth(1) {
+            Some(Statement::Fragment(fragment)) => {
+                assert_eq!(fragment.kind(), &FragmentKind::Alt);
+                assert_eq!(fragment.sections().len(), 2);
+                assert_eq!(fragment.sections()[1].statements().len(), 1);
+            }
+            statement => panic!("not a fragment: {:?}", statement),
+        }
+    }
+
+    #[test]
+    fn nested_unclosed_fragments() {
+        let input = "loop\nalt x\nA -> B: a";
+        assert_eq!(
+            errors(input),
+            [
+                (1, "fragment is not closed with `end`".to_string()),
+                (2, "fragment is not closed with `end`".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn theme_size_out_of_range() {
+        let input = "theme padding 9223372036854775807\ntheme font-size 0\ntheme padding 20";
+        let errors = errors(input);
+        assert_eq!(errors.len(), 2);
+        assert_eq!(
+            errors[0].1,
+            "theme `padding` must be from 1 to 1000, not 9223372036854775807"
+        );
+        assert_eq!(
+            errors[1].1,
+            "theme `font-size` must be from 1 to 1000, not 0"
+        );
+    }
+
+    #[test]
+    fn errors_in_source_order() {
+        // the unclosed fragment is only found at the end of the input, after
+        // the line that does not parse
+        let input = "alt x\nA -> B\nA -> B: ok\nend\nopt\nend\nend";
+        let lines = errors(input).into_iter().map(|(line, _)| line);
+        assert_eq!(lines.collect::<Vec<_>>(), [2, 7]);
+        let input = "alt x\nA -> B\nA -> B: ok";
+        let lines = errors(input).into_iter().map(|(line, _)| line);
+        assert_eq!(lines.collect::<Vec<_>>(), [1, 2]);
+    }
+
+    #[test]
+    fn keywords_end_at_word_boundaries() {
+        // neither opens a fragment that would swallow the signal after it
+        let input = "loops\noptional\nA -> B: hello";
+        let lines = errors(input).into_iter().map(|(line, _)| line);
+        assert_eq!(lines.collect::<Vec<_>>(), [1, 2]);
+        let document = parse(input).0;
+        assert!(matches!(document.iter().next(), Some(Statement::Signal(_))));
+
+        let input = "alt x\nelsewhere\nendless\nend";
+        let lines = errors(input).into_iter().map(|(line, _)| line);
+        assert_eq!(lines.collect::<Vec<_>>(), [2, 3]);
+    }
+
+    #[test]
+    fn keywords_before_guards() {
+        assert!(errors("loop\nend").is_empty());
+        assert!(errors("loop every minute\nend").is_empty());
+        assert!(errors("alt [ok]\nelse\nend # done").is_empty());
+        let (document, _) = parse("alt x\nelse other\nend");
+        match document.iter().next() {
+            Some(Statement::Fragment(fragment)) => {
+                let guards = fragment.sections().iter().map(Section::guard);
+                assert_eq!(guards.collect::<Vec<_>>(), [Some("x"), Some("other")]);
+            }
+            _ => panic!("not a fragment"),
+        }
+    }
+
+    #[test]
+    fn spans_cover_statements_and_actors() {
+        let input = "title: T\nA -> B: hi\n  note over A, B: n\nalt x\nA -> B: y\nend";
+        let (document, errors) = parse(input);
+        assert!(errors.is_empty());
+        let text = |span: Span| &input[span.start()..span.end()];
+        let statements = document.iter().map(|s| text(s.span()));
+        assert_eq!(
+            statements.collect::<Vec<_>>(),
+            [
+                "title: T",
+                "A -> B: hi",
+                "note over A, B: n",
+                "alt x\nA -> B: y\nend"
+            ]
+        );
+
+        let statements = document.iter().collect::<Vec<_>>();
+        match statements[1] {
+            Statement::Signal(signal) => {
+                assert_eq!(signal.from().span().start_line_col(), (2, 1));
+                assert_eq!(signal.to().span().start_line_col(), (2, 6));
+                assert_eq!(text(signal.signal().span()), "->");
+            }
+            _ => panic!("not a signal"),
+        }
+        let span = statements[2].span();
+        assert_eq!(span.start_line_col(), (3, 3));
+        assert_eq!(span.end_line_col(), (3, 20));
+    }
+
+    #[test]
+    fn unescape_newline_and_backslash() {
+        assert_eq!(unescape(r"one\ntwo"), "one\ntwo");
+        assert_eq!(unescape(r"C:\\temp"), r"C:\temp");
+        assert_eq!(unescape(r"a\\nb"), r"a\nb");
+        // other escapes are kept as they are
+        assert_eq!(unescape(r"a\tb\"), r"a\tb\");
+        assert!(matches!(unescape("plain"), Cow::Borrowed("plain")));
+    }
+
+    #[test]
+    fn escapes_in_messages() {
+        let (document, errors) = parse(r"A -> B: one\ntwo\\");
+        assert!(errors.is_empty());
+        match document.iter().next().unwrap() {
+            Statement::Signal(signal) => assert_eq!(signal.message(), "one\ntwo\\"),
+            _ => panic!("not a signal"),
+        }
+    }
+
+    /// The signal parsed from `input`, a single one.
+    fn signal_in(input: &str) -> Signal<'_> {
+        let (document, errors) = parse(input);
+        assert!(errors.is_empty(), "{:?}", errors);
+        match document.iter().next() {
+            Some(Statement::Signal(signal)) => signal.clone(),
+            _ => panic!("not a signal"),
+        }
+    }
+
+    #[test]
+    fn reversed_arrows() {
+        let signal = signal_in("B<-A: hi");
+        assert_eq!(signal.from().as_ref(), "B");
+        assert_eq!(signal.to().as_ref(), "A");
+        assert_eq!(signal.sender().as_ref(), "A");
+        assert_eq!(signal.receiver().as_ref(), "B");
+        assert!(signal.signal().is_reversed());
+        assert_eq!(signal.signal().line_type(), LineType::Normal);
+        assert_eq!(signal.signal().start_arrow_type(), ArrowType::Normal);
+        assert_eq!(signal.signal().arrow_type(), ArrowType::None);
+
+        let signal = signal_in("B<<--A: hi");
+        assert_eq!(signal.sender().as_ref(), "A");
+        assert!(signal.signal().is_reversed());
+        assert_eq!(signal.signal().line_type(), LineType::Dot);
+        assert_eq!(signal.signal().start_arrow_type(), ArrowType::Open);
+    }
+
+    #[test]
+    fn arrows_with_two_heads() {
+        let signal = signal_in("A<->B: hi");
+        assert_eq!(signal.sender().as_ref(), "A");
+        assert_eq!(signal.receiver().as_ref(), "B");
+        assert!(!signal.signal().is_reversed());
+        assert_eq!(signal.signal().start_arrow_type(), ArrowType::Normal);
+        assert_eq!(signal.signal().arrow_type(), ArrowType::Normal);
+
+        let signal = signal_in("A<<-->>B: hi");
+        assert!(!signal.signal().is_reversed());
+        assert_eq!(signal.signal().line_type(), LineType::Dot);
+        assert_eq!(signal.signal().start_arrow_type(), ArrowType::Open);
+        assert_eq!(signal.signal().arrow_type(), ArrowType::Open);
+    }
+
+    #[test]
+    fn forward_arrows_have_no_start_head() {
+        let signal = signal_in("A->>B: hi");
+        assert_eq!(signal.sender().as_ref(), "A");
+        assert!(!signal.signal().is_reversed());
+        assert_eq!(signal.signal().start_arrow_type(), ArrowType::None);
+        assert_eq!(signal.signal().arrow_type(), ArrowType::Open);
     }
-    Ok(result.into())
 }
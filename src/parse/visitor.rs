@@ -1,12 +1,20 @@
-use super::ast::{Document, Note, Participant, Signal, Statement, Title};
+use super::ast::{
+    Activate, Autonumber, Deactivate, Document, Fragment, Note, Participant, Signal, Statement,
+    Style, ThemeDirective, Title,
+};
 
-pub(crate) trait Visitor<'i> {
+/// Walks a [`Document`]. Each `accept` method calls the matching `visit_*`
+/// method; those left out fall back to [`Visitor::default_action`].
+///
+/// Visiting children is up to the implementation, e.g. `visit_document`
+/// usually calls `accept` on each statement.
+pub trait Visitor<'i> {
     type Context;
     type Output;
 
-    fn default_action(&self, _: &mut Self::Context) -> Self::Output {
-        unimplemented!()
-    }
+    /// What the `visit_*` methods that are not overridden do, such as
+    /// nothing, or returning an empty output.
+    fn default_action(&self, ctx: &mut Self::Context) -> Self::Output;
 
     fn visit_document(&self, _document: &Document<'i>, ctx: &mut Self::Context) -> Self::Output {
         self.default_action(ctx)
@@ -35,4 +43,36 @@ pub(crate) trait Visitor<'i> {
     fn visit_note(&self, _note: &Note<'i>, ctx: &mut Self::Context) -> Self::Output {
         self.default_action(ctx)
     }
+
+    fn visit_activate(&self, _activate: &Activate<'i>, ctx: &mut Self::Context) -> Self::Output {
+        self.default_action(ctx)
+    }
+
+    fn visit_deactivate(
+        &self,
+        _deactivate: &Deactivate<'i>,
+        ctx: &mut Self::Context,
+    ) -> Self::Output {
+        self.default_action(ctx)
+    }
+
+    fn visit_fragment(&self, _fragment: &Fragment<'i>, ctx: &mut Self::Context) -> Self::Output {
+        self.default_action(ctx)
+    }
+
+    fn visit_autonumber(
+        &self,
+        _autonumber: &Autonumber<'i>,
+        ctx: &mut Self::Context,
+    ) -> Self::Output {
+        self.default_action(ctx)
+    }
+
+    fn visit_style(&self, _style: &Style, ctx: &mut Self::Context) -> Self::Output {
+        self.default_action(ctx)
+    }
+
+    fn visit_theme(&self, _theme: &ThemeDirective<'i>, ctx: &mut Self::Context) -> Self::Output {
+        self.default_action(ctx)
+    }
 }
@@ -0,0 +1,135 @@
+//! Drawing diagrams as vector PDF documents, with the font embedded.
+
+use thiserror::Error;
+
+use crate::paper::{PageSetup, PdfBackend};
+use crate::{Document, FontError, FontMetrics, RenderOptions};
+
+const DEJAVU_SANS: &[u8] = include_bytes!("../fonts/DejaVuSans.ttf");
+const DEJAVU_SANS_MONO: &[u8] = include_bytes!("../fonts/DejaVuSansMono.ttf");
+
+/// How [`render_pdf`] lays a document out on pages.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct PdfOptions {
+    width: f32,
+    height: f32,
+    margin: f32,
+    font: Option<Vec<u8>>,
+}
+
+impl Default for PdfOptions {
+    fn default() -> Self {
+        Self {
+            width: Self::A4.0,
+            height: Self::A4.1,
+            margin: 36.0,
+            font: None,
+        }
+    }
+}
+
+impl PdfOptions {
+    /// Width and height of an A4 page, in points.
+    pub const A4: (f32, f32) = (595.0, 842.0);
+    /// Width and height of a US letter page, in points.
+    pub const LETTER: (f32, f32) = (612.0, 792.0);
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// In points, A4 by default. Diagrams wider than the page are scaled
+    /// down, longer ones continue on the next page.
+    pub fn with_page_size(self, width: f32, height: f32) -> Self {
+        Self {
+            width,
+            height,
+            ..self
+        }
+    }
+
+    /// Around the diagram on every page, in points.
+    pub fn with_margin(self, margin: f32) -> Self {
+        Self { margin, ..self }
+    }
+
+    /// The TrueType or OpenType font to embed and draw all text with,
+    /// instead of the bundled DejaVu Sans or DejaVu Sans Mono.
+    pub fn with_font(self, data: Vec<u8>) -> Self {
+        let font = Some(data);
+        Self { font, ..self }
+    }
+}
+
+/// A document that could not be written as PDF.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum PdfError {
+    #[error(transparent)]
+    Font(#[from] FontError),
+    #[error("no room for the diagram on a {0}x{1}pt page")]
+    PageSize(f32, f32),
+}
+
+/// Draw a document as a PDF document.
+pub fn render_pdf(
+    document: &Document<'_>,
+    options: &RenderOptions,
+    pdf: &PdfOptions,
+) -> Result<Vec<u8>, PdfError> {
+    if pdf.width <= 2.0 * pdf.margin || pdf.height <= 2.0 * pdf.margin {
+        return Err(PdfError::PageSize(pdf.width, pdf.height));
+    }
+
+    let paper = crate::draw(document, options);
+    let font = match &pdf.font {
+        Some(font) => font.as_slice(),
+        None if paper.theme.font_metrics == FontMetrics::dejavu_sans_mono() => DEJAVU_SANS_MONO,
+        None => DEJAVU_SANS,
+    };
+    let setup = PageSetup {
+        width: pdf.width,
+        height: pdf.height,
+        margin: pdf.margin,
+    };
+    Ok(paper.render(PdfBackend::new(setup, font)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pages(pdf: &[u8]) -> usize {
+        let count = |needle: &[u8]| pdf.windows(needle.len()).filter(|w| w == &needle).count();
+        count(b"/Type /Page") - count(b"/Type /Pages")
+    }
+
+    #[test]
+    fn pdf_document() {
+        let document = crate::parse("Alice -> Bob: hello").unwrap();
+        let pdf = render_pdf(&document, &RenderOptions::default(), &PdfOptions::new()).unwrap();
+        assert!(pdf.starts_with(b"%PDF-"));
+        assert!(pdf.trim_ascii_end().ends_with(b"%%EOF"));
+        assert_eq!(pages(&pdf), 1);
+    }
+
+    #[test]
+    fn long_diagrams_continue_on_more_pages() {
+        let text = (0..100)
+            .map(|i| format!("Alice -> Bob: message {}", i))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let document = crate::parse(&text).unwrap();
+        let pdf = render_pdf(&document, &RenderOptions::default(), &PdfOptions::new()).unwrap();
+        assert!(pages(&pdf) > 1);
+    }
+
+    #[test]
+    fn pages_too_small_are_refused() {
+        let document = crate::parse("Alice -> Bob: hello").unwrap();
+        let pdf = PdfOptions::new().with_page_size(60.0, 60.0);
+        let error = render_pdf(&document, &RenderOptions::default(), &pdf).unwrap_err();
+        assert!(matches!(error, PdfError::PageSize(..)));
+    }
+}
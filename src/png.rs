@@ -0,0 +1,165 @@
+//! Drawing diagrams as PNG images, with bundled fonts so they look the same
+//! on every machine.
+
+use resvg::{tiny_skia, usvg};
+use thiserror::Error;
+
+use crate::{Document, RenderOptions, SvgBackend};
+
+const DEJAVU_SANS: &[u8] = include_bytes!("../fonts/DejaVuSans.ttf");
+const DEJAVU_SANS_MONO: &[u8] = include_bytes!("../fonts/DejaVuSansMono.ttf");
+
+/// What is behind the diagram in a PNG.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Background {
+    /// The `background` of the theme, or its `fill` if it has none.
+    #[default]
+    Theme,
+    Transparent,
+    /// Any SVG color.
+    Color(String),
+}
+
+/// How [`render_png`] rasterizes a document.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct PngOptions {
+    scale: f32,
+    background: Background,
+    fonts: Vec<Vec<u8>>,
+}
+
+impl Default for PngOptions {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            background: Background::default(),
+            fonts: vec![],
+        }
+    }
+}
+
+impl PngOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Pixels per SVG pixel.
+    pub fn with_scale(self, scale: f32) -> Self {
+        Self { scale, ..self }
+    }
+
+    /// Scale for printing at `dpi`, SVG pixels being 1/96 inch.
+    pub fn with_dpi(self, dpi: f32) -> Self {
+        self.with_scale(dpi / 96.0)
+    }
+
+    pub fn with_background(self, background: Background) -> Self {
+        Self { background, ..self }
+    }
+
+    /// Add a TrueType or OpenType font to draw with, besides the bundled
+    /// DejaVu Sans and DejaVu Sans Mono.
+    pub fn with_font(mut self, data: Vec<u8>) -> Self {
+        self.fonts.push(data);
+        self
+    }
+}
+
+/// A document that could not be rasterized.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum PngError {
+    #[error("cannot read the drawn SVG: {0}")]
+    Svg(#[from] usvg::Error),
+    #[error("cannot make a {0}x{1} image")]
+    Size(u32, u32),
+    #[error("cannot encode PNG: {0}")]
+    Encode(String),
+}
+
+/// Draw a document as a PNG image.
+pub fn render_png(
+    document: &Document<'_>,
+    options: &RenderOptions,
+    png: &PngOptions,
+) -> Result<Vec<u8>, PngError> {
+    let mut paper = crate::draw(document, options);
+    let theme = &mut paper.theme;
+    match &png.background {
+        Background::Theme if theme.background.is_none() => {
+            theme.background = Some(theme.fill.clone())
+        }
+        Background::Theme => {}
+        Background::Transparent => theme.background = None,
+        Background::Color(color) => theme.background = Some(color.clone()),
+    }
+    let svg = paper.render(SvgBackend::new());
+
+    let mut usvg_options = usvg::Options {
+        font_family: "DejaVu Sans".to_string(),
+        ..usvg::Options::default()
+    };
+    let fontdb = usvg_options.fontdb_mut();
+    fontdb.load_font_data(DEJAVU_SANS.to_vec());
+    fontdb.load_font_data(DEJAVU_SANS_MONO.to_vec());
+    for font in &png.fonts {
+        fontdb.load_font_data(font.clone());
+    }
+    fontdb.set_sans_serif_family("DejaVu Sans");
+    fontdb.set_serif_family("DejaVu Sans");
+    fontdb.set_monospace_family("DejaVu Sans Mono");
+    let tree = usvg::Tree::from_str(&svg, &usvg_options)?;
+
+    let size = tree.size();
+    let width = (size.width() * png.scale).ceil() as u32;
+    let height = (size.height() * png.scale).ceil() as u32;
+    let mut pixmap = tiny_skia::Pixmap::new(width, height).ok_or(PngError::Size(width, height))?;
+    let transform = tiny_skia::Transform::from_scale(png.scale, png.scale);
+    resvg::render(&tree, transform, &mut pixmap.as_mut());
+    pixmap
+        .encode_png()
+        .map_err(|e| PngError::Encode(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::TryInto;
+
+    /// Width and height from the `IHDR` chunk of a PNG.
+    fn size(png: &[u8]) -> (u32, u32) {
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&png[12..16], b"IHDR");
+        let number = |at: usize| u32::from_be_bytes(png[at..at + 4].try_into().unwrap());
+        (number(16), number(20))
+    }
+
+    #[test]
+    fn png_is_as_large_as_the_paper_times_the_scale() {
+        let document = crate::parse("Alice -> Bob: hello\nnote over Bob: hi").unwrap();
+        let options = RenderOptions::default();
+        let paper = crate::draw(&document, &options);
+        let (width, height) = (paper.width.ceil() as u32, paper.height.ceil() as u32);
+
+        let png = render_png(&document, &options, &PngOptions::new()).unwrap();
+        assert_eq!(size(&png), (width, height));
+        let png = render_png(&document, &options, &PngOptions::new().with_scale(2.0)).unwrap();
+        assert_eq!(size(&png), (width * 2, height * 2));
+    }
+
+    #[test]
+    fn png_with_any_background() {
+        let document = crate::parse("Alice -> Bob: hello").unwrap();
+        let options = RenderOptions::default();
+        for background in [
+            Background::Theme,
+            Background::Transparent,
+            Background::Color("#336699".to_string()),
+        ] {
+            let png = PngOptions::new().with_background(background);
+            assert!(render_png(&document, &options, &png).is_ok());
+        }
+    }
+}
@@ -0,0 +1,54 @@
+//! The source text packed into the URL hash of a shared diagram.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::engine::GeneralPurpose;
+use base64::Engine as _;
+use lz4_compression::prelude::{compress, decompress};
+use thiserror::Error;
+
+const ENCODER: GeneralPurpose = URL_SAFE_NO_PAD;
+
+/// `text` as a `/v1/...` hash.
+pub(crate) fn pickle(text: &str) -> String {
+    let compressed = compress(text.as_bytes());
+    let pickled = ENCODER.encode(&compressed);
+    format!("/v1/{}", pickled)
+}
+
+#[derive(Error, Debug)]
+pub(crate) enum LoadAndGenError {
+    #[error("unexpected hash")]
+    UnexpectedHash,
+
+    #[error("decode error")]
+    DecodeError(#[from] base64::DecodeError),
+
+    #[error("decompress error")]
+    DecompressError(lz4_compression::decompress::Error),
+
+    #[error("from utf8 error")]
+    FromUtf8Error(#[from] std::string::FromUtf8Error),
+}
+
+/// The text packed by [`pickle`], with or without the leading `#`.
+pub(crate) fn unpickle(hash: &str) -> Result<String, LoadAndGenError> {
+    if hash.is_empty() {
+        return Ok("".to_owned());
+    }
+    let mut parts = hash.split('/');
+    parts.next();
+    match parts.next() {
+        Some("v1") => {}
+        _ => return Err(LoadAndGenError::UnexpectedHash),
+    }
+    let pickled = if let Some(text) = parts.next() {
+        text
+    } else {
+        return Err(LoadAndGenError::UnexpectedHash);
+    };
+    let compressed = ENCODER.decode(pickled)?;
+    let text = decompress(&compressed).map_err(LoadAndGenError::DecompressError)?;
+    let text = String::from_utf8(text)?;
+
+    Ok(text)
+}
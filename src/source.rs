@@ -0,0 +1,43 @@
+//! The diagram source kept in the SVG drawn from it, so that the text can be
+//! recovered from the image alone.
+
+use thiserror::Error;
+
+/// Namespace of the `<source>` element in the SVG `<metadata>`.
+pub(crate) const NAMESPACE: &str = "https://github.com/yskszk63/seqdia";
+
+/// Written as the `version` of the `<source>` element, for the day its
+/// content is anything but the plain text.
+pub(crate) const VERSION: &str = "1";
+
+/// An SVG the diagram source could not be read back from.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum SourceError {
+    #[error("cannot read SVG: {0}")]
+    Svg(#[from] roxmltree::Error),
+    #[error("no diagram source in the SVG")]
+    Missing,
+    #[error("diagram source of unknown version {0:?}")]
+    Version(String),
+}
+
+/// The text an SVG was drawn from, as embedded by [`generate`] or
+/// [`SvgBackend::with_source`].
+///
+/// [`generate`]: crate::generate
+/// [`SvgBackend::with_source`]: crate::SvgBackend::with_source
+pub fn source_from_svg(svg: &str) -> Result<String, SourceError> {
+    let document = roxmltree::Document::parse(svg)?;
+    let source = document
+        .descendants()
+        .find(|node| {
+            node.tag_name().namespace() == Some(NAMESPACE) && node.tag_name().name() == "source"
+        })
+        .ok_or(SourceError::Missing)?;
+    match source.attribute("version") {
+        Some(VERSION) => {}
+        version => return Err(SourceError::Version(version.unwrap_or("").to_string())),
+    }
+    Ok(source.children().filter_map(|node| node.text()).collect())
+}
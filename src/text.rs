@@ -0,0 +1,745 @@
+//! Drawing diagrams on a grid of characters, for code comments, commit
+//! messages and terminals.
+
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::{
+    spread_gaps, Actor, ArrowType, Document, Layout, LineType, Note, RenderOptions,
+    SequenceDiagram, SignalKind, TextAnchor,
+};
+
+/// Columns between a lifeline and the far side of a signal to itself.
+const SELF_SIGNAL_WIDTH: isize = 3;
+
+/// How [`render_text`] draws a document.
+#[derive(Debug, Clone, Default)]
+#[non_exhaustive]
+pub struct TextOptions {
+    ascii: bool,
+}
+
+impl TextOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Draw with `-`, `|` and `+` only, instead of box-drawing characters.
+    pub fn with_ascii(self, ascii: bool) -> Self {
+        Self { ascii }
+    }
+}
+
+/// The characters lines and boxes are drawn with.
+#[derive(Debug)]
+struct Glyphs {
+    horizontal: char,
+    vertical: char,
+    dotted_vertical: char,
+    top_left: char,
+    top_right: char,
+    bottom_left: char,
+    bottom_right: char,
+    /// where a lifeline leaves the bottom of a box
+    tee_down: char,
+    /// where a lifeline enters the top of a box
+    tee_up: char,
+    tee_right: char,
+    tee_left: char,
+    cross: char,
+}
+
+const UNICODE: Glyphs = Glyphs {
+    horizontal: '─',
+    vertical: '│',
+    dotted_vertical: '┆',
+    top_left: '┌',
+    top_right: '┐',
+    bottom_left: '└',
+    bottom_right: '┘',
+    tee_down: '┬',
+    tee_up: '┴',
+    tee_right: '├',
+    tee_left: '┤',
+    cross: '┼',
+};
+
+const ASCII: Glyphs = Glyphs {
+    horizontal: '-',
+    vertical: '|',
+    dotted_vertical: ':',
+    top_left: '+',
+    top_right: '+',
+    bottom_left: '+',
+    bottom_right: '+',
+    tee_down: '+',
+    tee_up: '+',
+    tee_right: '+',
+    tee_left: '+',
+    cross: '+',
+};
+
+/// Cells of text, wide characters taking up two.
+#[derive(Debug, Default)]
+struct Canvas {
+    rows: Vec<Vec<Option<char>>>,
+}
+
+impl Canvas {
+    fn get(&self, x: isize, y: isize) -> Option<char> {
+        self.rows
+            .get(y as usize)?
+            .get(x as usize)
+            .copied()
+            .flatten()
+    }
+
+    fn set(&mut self, x: isize, y: isize, c: char) {
+        if x < 0 || y < 0 {
+            return;
+        }
+        let (x, y) = (x as usize, y as usize);
+        let width = c.width().unwrap_or(0).max(1);
+        if self.rows.len() <= y {
+            self.rows.resize(y + 1, vec![]);
+        }
+        let row = &mut self.rows[y];
+        if row.len() < x + width {
+            row.resize(x + width, Some(' '));
+        }
+        row[x] = Some(c);
+        // the cell covered by the right half of a wide character
+        if width == 2 {
+            row[x + 1] = None;
+        }
+    }
+
+    /// Write `text` from `x`, each of its lines on a row of its own.
+    fn text(&mut self, x: isize, y: isize, text: &str) {
+        for (i, line) in text.lines().enumerate() {
+            let mut x = x;
+            for c in line.chars() {
+                self.set(x, y + i as isize, c);
+                x += c.width().unwrap_or(0) as isize;
+            }
+        }
+    }
+
+    fn finish(self) -> String {
+        let mut out = String::new();
+        for row in self.rows {
+            let line = row.into_iter().flatten().collect::<String>();
+            out.push_str(line.trim_end());
+            out.push('\n');
+        }
+        out
+    }
+}
+
+/// Columns taken by the longest line of `text`.
+fn width(text: &str) -> isize {
+    text.lines()
+        .map(|line| UnicodeWidthStr::width(line) as isize)
+        .max()
+        .unwrap_or(0)
+}
+
+/// Rows taken by `text`, one at least.
+fn height(text: &str) -> isize {
+    text.lines().count().max(1) as isize
+}
+
+/// `text` on a single row, for the edge of a frame.
+fn one_line(text: &str) -> String {
+    text.lines().collect::<Vec<_>>().join(" ")
+}
+
+/// The character `i` cells along a line drawn with `c`, dotted lines
+/// leaving every other cell blank.
+fn dotted(line: &LineType, i: isize, c: char) -> char {
+    match line {
+        LineType::Dot if i % 2 == 1 => ' ',
+        _ => c,
+    }
+}
+
+#[derive(Debug)]
+struct TextDiagram<'l, 'i> {
+    layout: &'l Layout<'i>,
+    glyphs: &'static Glyphs,
+    canvas: Canvas,
+    /// column of each lifeline
+    lifelines: Vec<isize>,
+}
+
+impl<'l, 'i> TextDiagram<'l, 'i> {
+    /// A box around `text` is this wide.
+    fn box_width(text: &str) -> isize {
+        width(text) + 4
+    }
+
+    fn index(&self, actor: &Actor<'_>) -> usize {
+        self.layout.index_by_actor(actor).unwrap()
+    }
+
+    /// Place the lifelines, the same way the SVG layout does but counting
+    /// columns.
+    fn place(&mut self) {
+        let layout = self.layout;
+        let len = layout.actors.len();
+        let actor_width = |i: usize| Self::box_width(&layout.wrap(layout.actors[i].1.as_ref()));
+        let mut gaps = (0..=len)
+            .map(|i| match i {
+                0 if len == 0 => 0,
+                0 => actor_width(0) / 2,
+                i if i == len => actor_width(len - 1) - actor_width(len - 1) / 2,
+                i => actor_width(i - 1) - actor_width(i - 1) / 2 + 1 + actor_width(i) / 2,
+            })
+            .collect::<Vec<_>>();
+
+        // (first gap, last gap + 1, width)
+        let mut spans = vec![];
+        for (signal, _) in &layout.signals {
+            match signal {
+                SignalKind::Signal(signal, _, label) => {
+                    let a = self.index(signal.from()) + 1;
+                    let b = self.index(signal.to()) + 1;
+                    let label = width(label);
+                    if a == b {
+                        spans.push((a, a + 1, SELF_SIGNAL_WIDTH + label + 3));
+                    } else {
+                        spans.push((usize::min(a, b), usize::max(a, b), label + 4));
+                    }
+                }
+                SignalKind::Note(note, message) => {
+                    let mut indices = note
+                        .actors()
+                        .into_iter()
+                        .map(|actor| self.index(actor) + 1)
+                        .collect::<Vec<_>>();
+                    indices.sort_unstable();
+                    let a = indices[0];
+                    let b = indices[indices.len() - 1];
+                    let w = Self::box_width(message);
+                    match note {
+                        Note::LeftOf(..) => spans.push((a - 1, a, w + 2)),
+                        Note::RightOf(..) => spans.push((a, a + 1, w + 3)),
+                        Note::Over(..) if a == b => {
+                            spans.push((a - 1, a, w / 2 + 1));
+                            spans.push((a, a + 1, w - w / 2 + 1));
+                        }
+                        Note::Over(..) => spans.push((a, b, w - 4)),
+                    }
+                }
+                SignalKind::Section(index, 0) => {
+                    let (a, b) = match layout.fragment_actors(*index) {
+                        Some((a, b)) => (a + 1, b + 1),
+                        None if len > 0 => (1, len),
+                        None => continue,
+                    };
+                    let padding = self.fragment_padding(*index);
+                    // past the rightmost lifeline, around any signals to itself
+                    let mut right = layout
+                        .fragment_rows(*index)
+                        .iter()
+                        .filter_map(|(signal, _)| match signal {
+                            SignalKind::Signal(signal, _, label)
+                                if signal.from() == signal.to()
+                                    && self.index(signal.from()) + 1 == b =>
+                            {
+                                Some(SELF_SIGNAL_WIDTH + 2 + width(label) + padding)
+                            }
+                            _ => None,
+                        })
+                        .fold(padding, isize::max);
+                    let header = self.fragment_header(*index);
+                    if a == b {
+                        right = right.max(header - padding - 1);
+                    } else {
+                        spans.push((a, b, header - padding - right - 1));
+                    }
+                    spans.push((a - 1, a, padding + 2));
+                    spans.push((b, b + 1, right + 2));
+                }
+                SignalKind::Section(..) | SignalKind::End(_) => {}
+            }
+        }
+
+        spread_gaps(&mut gaps, spans);
+
+        let mut x = 0;
+        self.lifelines = gaps[..len]
+            .iter()
+            .map(|gap| {
+                x += gap;
+                x
+            })
+            .collect();
+    }
+
+    /// Columns between a fragment's frame and the lifelines in it, growing
+    /// with the depth of the fragments nested inside.
+    fn fragment_padding(&self, index: usize) -> isize {
+        let depth = self.layout.fragments[index].1;
+        let inner = self
+            .layout
+            .fragment_rows(index)
+            .iter()
+            .filter_map(|(signal, _)| match signal {
+                SignalKind::End(i) => Some(self.layout.fragments[*i].1),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(depth);
+        2 * (1 + inner - depth) + 1
+    }
+
+    /// Width the frame of a fragment needs for the text on its edges.
+    fn fragment_header(&self, index: usize) -> isize {
+        let fragment = &self.layout.fragments[index].0;
+        fragment
+            .sections()
+            .iter()
+            .enumerate()
+            .map(|(i, section)| {
+                let guard = section
+                    .guard()
+                    .map_or(0, |guard| width(&one_line(guard)) + 3);
+                let kind = if i == 0 {
+                    width(fragment.kind().as_ref()) + 1
+                } else {
+                    0
+                };
+                kind + guard + 6
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// First and last column of a fragment's frame.
+    fn fragment_x(&self, index: usize) -> (isize, isize) {
+        let padding = self.fragment_padding(index);
+        let mut left = isize::MAX;
+        let mut right = isize::MIN;
+        for (signal, _) in self.layout.fragment_rows(index) {
+            match signal {
+                SignalKind::Signal(signal, _, label) => {
+                    for actor in &[signal.from(), signal.to()] {
+                        let x = self.lifelines[self.index(actor)];
+                        left = left.min(x - padding);
+                        right = right.max(x + padding);
+                    }
+                    if signal.from() == signal.to() {
+                        let x = self.lifelines[self.index(signal.from())];
+                        right = right.max(x + SELF_SIGNAL_WIDTH + 2 + width(label) + padding);
+                    }
+                }
+                SignalKind::Note(note, message) => {
+                    let (x, w) = self.note_x(note, message);
+                    left = left.min(x - padding + 2);
+                    right = right.max(x + w - 1 + padding - 2);
+                }
+                SignalKind::End(i) if *i != index => {
+                    let (l, r) = self.fragment_x(*i);
+                    left = left.min(l - 2);
+                    right = right.max(r + 2);
+                }
+                SignalKind::Section(..) | SignalKind::End(_) => {}
+            }
+        }
+        if left > right {
+            left = self.lifelines.first().copied().unwrap_or(padding) - padding;
+            right = self.lifelines.last().copied().unwrap_or(padding) + padding;
+        }
+        (left, right.max(left + self.fragment_header(index) - 1))
+    }
+
+    /// First column and width of the box drawn for a note with `message`.
+    fn note_x(&self, note: &Note<'_>, message: &str) -> (isize, isize) {
+        let w = Self::box_width(message);
+        match note {
+            Note::LeftOf(actor, ..) => (self.lifelines[self.index(actor)] - 1 - w, w),
+            Note::RightOf(actor, ..) => (self.lifelines[self.index(actor)] + 2, w),
+            Note::Over(actor, None, ..) => (self.lifelines[self.index(actor)] - w / 2, w),
+            Note::Over(actor, Some(actor2), ..) => {
+                let x1 = self.lifelines[self.index(actor)];
+                let x2 = self.lifelines[self.index(actor2)];
+                // overhanging both lifelines by two columns at least
+                let span = (x2 - x1).abs() + 5;
+                if w <= span {
+                    (x1.min(x2) - 2, span)
+                } else {
+                    ((x1 + x2) / 2 - w / 2, w)
+                }
+            }
+        }
+    }
+
+    /// A box `w` columns wide and `h` lines high with `text` inside, each of
+    /// its lines placed by `anchor`.
+    #[allow(clippy::too_many_arguments)]
+    fn draw_box(&mut self, x: isize, y: isize, w: isize, h: isize, text: &str, anchor: TextAnchor) {
+        let g = self.glyphs;
+        self.canvas.set(x, y, g.top_left);
+        self.canvas.set(x, y + h - 1, g.bottom_left);
+        for i in 1..w - 1 {
+            self.canvas.set(x + i, y, g.horizontal);
+            for j in 1..h - 1 {
+                self.canvas.set(x + i, y + j, ' ');
+            }
+            self.canvas.set(x + i, y + h - 1, g.horizontal);
+        }
+        self.canvas.set(x + w - 1, y, g.top_right);
+        self.canvas.set(x + w - 1, y + h - 1, g.bottom_right);
+        for j in 1..h - 1 {
+            self.canvas.set(x, y + j, g.vertical);
+            self.canvas.set(x + w - 1, y + j, g.vertical);
+        }
+        // centred vertically in boxes taller than the text
+        let top = y + 1 + (h - 2 - height(text)) / 2;
+        for (i, line) in text.lines().enumerate() {
+            let indent = match anchor {
+                TextAnchor::Start => 0,
+                TextAnchor::Middle => (w - 4 - width(line)) / 2,
+                TextAnchor::End => w - 4 - width(line),
+            };
+            self.canvas.text(x + 2 + indent, top + i as isize, line);
+        }
+    }
+
+    /// Rows taken by the boxes of the actors, all as high as the highest.
+    fn actor_height(&self) -> isize {
+        self.layout
+            .actors
+            .iter()
+            .map(|(_, name, _)| height(&self.layout.wrap(name.as_ref())) + 2)
+            .max()
+            .unwrap_or(0)
+    }
+
+    fn draw_actors(&mut self, y: isize, top: bool) {
+        let h = self.actor_height();
+        for (i, (_, name, _)) in self.layout.actors.iter().enumerate() {
+            let name = self.layout.wrap(name.as_ref());
+            let x = self.lifelines[i];
+            let w = Self::box_width(&name);
+            self.draw_box(x - w / 2, y, w, h, &name, TextAnchor::Middle);
+            if top {
+                self.canvas.set(x, y + h - 1, self.glyphs.tee_down);
+            } else {
+                self.canvas.set(x, y, self.glyphs.tee_up);
+            }
+        }
+    }
+
+    fn draw_lifelines(&mut self, y: isize) {
+        for x in self.lifelines.clone() {
+            self.canvas.set(x, y, self.glyphs.vertical);
+        }
+    }
+
+    /// A horizontal line from `x1` to `x2`, both inclusive, crossing any
+    /// lifelines on the way. Dotted lines have a dash at `x1`.
+    fn draw_line(&mut self, x1: isize, x2: isize, y: isize, line: &LineType) {
+        let g = self.glyphs;
+        for x in x1.min(x2)..=x1.max(x2) {
+            let c = dotted(line, (x - x1).abs(), g.horizontal);
+            match self.canvas.get(x, y) {
+                Some(v) if v == g.vertical && c == ' ' => {}
+                Some(v) if v == g.vertical => self.canvas.set(x, y, g.cross),
+                _ => self.canvas.set(x, y, c),
+            }
+        }
+    }
+
+    /// An arrow from `x1` to `x2`, neither of which is part of it, with
+    /// `start` and `end` heads pointing at them.
+    fn draw_arrow(
+        &mut self,
+        x1: isize,
+        x2: isize,
+        y: isize,
+        line: &LineType,
+        start: ArrowType,
+        end: ArrowType,
+    ) {
+        let right = x2 > x1;
+        let (head, back, step) = if right { ('>', '<', -1) } else { ('<', '>', 1) };
+        let heads = |arrow| match arrow {
+            ArrowType::None => 0,
+            ArrowType::Normal => 1,
+            ArrowType::Open => 2,
+        };
+        let (start_heads, end_heads) = (heads(start), heads(end));
+        // dotted lines end in a dash next to the head, at the end if both
+        // have one
+        let (from, to) = (x2 + step * (end_heads + 1), x1 - step * (start_heads + 1));
+        if (to - from) * step >= 0 && end_heads == 0 && start_heads > 0 {
+            self.draw_line(to, from, y, line);
+        } else if (to - from) * step >= 0 {
+            self.draw_line(from, to, y, line);
+        }
+        for i in 0..end_heads {
+            self.canvas.set(x2 + step * (i + 1), y, head);
+        }
+        for i in 0..start_heads {
+            self.canvas.set(x1 - step * (i + 1), y, back);
+        }
+    }
+
+    fn draw_section(&mut self, y: isize, index: usize, section: usize) {
+        let g = self.glyphs;
+        let (left, right) = self.fragment_x(index);
+        let fragment = &self.layout.fragments[index].0;
+        let line = if section == 0 {
+            self.canvas.set(left, y, g.top_left);
+            self.canvas.set(right, y, g.top_right);
+            LineType::Normal
+        } else {
+            self.canvas.set(left, y, g.tee_right);
+            self.canvas.set(right, y, g.tee_left);
+            LineType::Dot
+        };
+        self.draw_line(left + 1, right - 1, y, &line);
+
+        let mut text = String::new();
+        if section == 0 {
+            text.push_str(fragment.kind().as_ref());
+        }
+        if let Some(guard) = fragment.sections()[section].guard() {
+            if !text.is_empty() {
+                text.push(' ');
+            }
+            text.push_str(&format!("[{}]", one_line(guard)));
+        }
+        if !text.is_empty() {
+            self.canvas.text(left + 2, y, &format!(" {} ", text));
+        }
+    }
+
+    fn draw_end(&mut self, y: isize, index: usize) {
+        let g = self.glyphs;
+        let (left, right) = self.fragment_x(index);
+        self.canvas.set(left, y, g.bottom_left);
+        self.canvas.set(right, y, g.bottom_right);
+        self.draw_line(left + 1, right - 1, y, &LineType::Normal);
+    }
+
+    /// Draw a row, returning how many lines it took.
+    fn draw_row(&mut self, y: isize, signal: &SignalKind<'i>) -> isize {
+        let g = self.glyphs;
+        match signal {
+            SignalKind::Signal(signal, _, label) => {
+                let x1 = self.lifelines[self.index(signal.from())];
+                let x2 = self.lifelines[self.index(signal.to())];
+                let line = signal.signal().line_type();
+                let start = signal.signal().start_arrow_type();
+                let arrow = signal.signal().arrow_type();
+                let lines = height(label);
+                if x1 == x2 {
+                    let corner = x1 + SELF_SIGNAL_WIDTH;
+                    for i in 1..=lines + 1 {
+                        self.draw_lifelines(y + i);
+                    }
+                    self.draw_arrow(corner, x1, y, &line, ArrowType::None, start);
+                    self.canvas.set(corner, y, g.top_right);
+                    for i in 1..=lines {
+                        self.canvas.set(
+                            corner,
+                            y + i,
+                            match line {
+                                LineType::Normal => g.vertical,
+                                LineType::Dot => g.dotted_vertical,
+                            },
+                        );
+                    }
+                    self.canvas.set(corner, y + lines + 1, g.bottom_right);
+                    self.draw_arrow(corner, x1, y + lines + 1, &line, ArrowType::None, arrow);
+                    self.canvas.text(corner + 2, y + 1, label);
+                    lines + 2
+                } else {
+                    for i in 1..=lines {
+                        self.draw_lifelines(y + i);
+                    }
+                    self.canvas.text(x1.min(x2) + 2, y, label);
+                    self.draw_arrow(x1, x2, y + lines, &line, start, arrow);
+                    lines + 1
+                }
+            }
+            SignalKind::Note(note, message) => {
+                let h = height(message) + 2;
+                for i in 1..h {
+                    self.draw_lifelines(y + i);
+                }
+                let (x, w) = self.note_x(note, message);
+                self.draw_box(x, y, w, h, message, TextAnchor::Start);
+                if let Note::Over(..) = note {
+                    for actor in note.actors() {
+                        let x = self.lifelines[self.index(actor)];
+                        self.canvas.set(x, y, g.tee_up);
+                        self.canvas.set(x, y + h - 1, g.tee_down);
+                    }
+                }
+                h
+            }
+            SignalKind::Section(index, section) => {
+                self.draw_section(y, *index, *section);
+                1
+            }
+            SignalKind::End(index) => {
+                self.draw_end(y, *index);
+                1
+            }
+        }
+    }
+
+    fn draw(mut self) -> String {
+        self.place();
+
+        // shift everything right if a note or frame sticks out on the left
+        let left = self
+            .layout
+            .signals
+            .iter()
+            .filter_map(|(signal, _)| match signal {
+                SignalKind::Note(note, message) => Some(self.note_x(note, message).0),
+                SignalKind::Section(index, 0) => Some(self.fragment_x(*index).0),
+                _ => None,
+            })
+            .chain(
+                self.layout
+                    .actors
+                    .iter()
+                    .enumerate()
+                    .map(|(i, (_, name, _))| {
+                        self.lifelines[i] - Self::box_width(&self.layout.wrap(name.as_ref())) / 2
+                    }),
+            )
+            .min()
+            .unwrap_or(0);
+        for x in &mut self.lifelines {
+            *x -= left;
+        }
+
+        let mut y = 0;
+        if let Some((title, _)) = &self.layout.title {
+            let w = Self::box_width(title.as_ref());
+            let h = height(title.as_ref()) + 2;
+            self.draw_box(0, 0, w, h, title.as_ref(), TextAnchor::Middle);
+            y += h + 1;
+        }
+        let actor_height = self.actor_height();
+        self.draw_actors(y, true);
+        y += actor_height;
+
+        // fragments whose sides are drawn next to each row
+        let mut open = vec![];
+        for (signal, _) in &self.layout.signals {
+            self.draw_lifelines(y);
+            let lines = self.draw_row(y, signal);
+            let current = match signal {
+                SignalKind::Section(index, _) | SignalKind::End(index) => Some(*index),
+                SignalKind::Signal(..) | SignalKind::Note(..) => None,
+            };
+            if let SignalKind::End(_) = signal {
+                open.pop();
+            }
+            for line in 0..lines {
+                for &index in open.iter().filter(|&&index| Some(index) != current) {
+                    let (left, right) = self.fragment_x(index);
+                    self.canvas.set(left, y + line, self.glyphs.vertical);
+                    self.canvas.set(right, y + line, self.glyphs.vertical);
+                }
+            }
+            if let SignalKind::Section(index, 0) = signal {
+                open.push(*index);
+            }
+            y += lines;
+        }
+        if actor_height > 0 {
+            self.draw_lifelines(y);
+            y += 1;
+        }
+
+        self.draw_actors(y, false);
+        self.canvas.finish()
+    }
+}
+
+/// Draw a document with box-drawing characters, or plain ASCII.
+pub fn render_text(document: &Document<'_>, options: &TextOptions) -> String {
+    let layout = SequenceDiagram::new(document, &RenderOptions::default()).layout();
+    TextDiagram {
+        layout: &layout,
+        glyphs: if options.ascii { &ASCII } else { &UNICODE },
+        canvas: Canvas::default(),
+        lifelines: vec![],
+    }
+    .draw()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(source: &str, ascii: bool) -> String {
+        let document = crate::parse(source).unwrap();
+        render_text(&document, &TextOptions::new().with_ascii(ascii))
+    }
+
+    #[test]
+    fn signals_between_lifelines() {
+        let expected = "\
+┌───┐ ┌───┐
+│ A │ │ B │
+└─┬─┘ └─┬─┘
+  │ hi  │
+  │────>│
+  │     │─ ┐
+  │     │  ┆ self
+  │     │<<┘
+  │     │
+┌─┴─┐ ┌─┴─┐
+│ A │ │ B │
+└───┘ └───┘
+";
+        assert_eq!(text("A -> B: hi\nB -->> B: self", false), expected);
+    }
+
+    #[test]
+    fn ascii_only() {
+        let expected = "\
++---+ +---+
+| A | | B |
++-+-+ +-+-+
+  | hi  |
+  |---->|
+  |     |- +
+  |     |  : self
+  |     |<<+
+  |     |
++-+-+ +-+-+
+| A | | B |
++---+ +---+
+";
+        assert_eq!(text("A -> B: hi\nB -->> B: self", true), expected);
+    }
+
+    #[test]
+    fn reversed_dotted_signals_and_notes() {
+        let expected = "\
+┌───┐   ┌───┐
+│ A │   │ B │
+└─┬─┘   └─┬─┘
+  │ back  │
+  │<─ ─ ─ │
+┌─┴───────┴─┐
+│ both      │
+└─┬───────┬─┘
+  │       │
+┌─┴─┐   ┌─┴─┐
+│ A │   │ B │
+└───┘   └───┘
+";
+        assert_eq!(text("A <-- B: back\nnote over A, B: both", false), expected);
+    }
+}
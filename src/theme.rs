@@ -0,0 +1,219 @@
+use std::ops::RangeInclusive;
+
+use crate::parse::ThemeDirective;
+use crate::FontMetrics;
+
+/// Values the sizes set by [`Theme::set`] are clamped to, in pixels, so that
+/// the layout stays within bounds.
+const SIZES: &[(&str, RangeInclusive<isize>)] = &[
+    ("font-size", 1..=1000),
+    ("line-width", 1..=100),
+    ("padding", 1..=1000),
+    ("note-padding", 1..=1000),
+    ("max-text-width", 1..=10000),
+];
+
+/// Fonts, colors and sizes a diagram is drawn with.
+///
+/// Start from one of the built-in themes and change what is needed:
+///
+/// ```
+/// let mut theme = seqdia::Theme::dark();
+/// theme.font_size = 20;
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Theme {
+    /// `font-family` of all text, inherited from the page if `None`
+    pub font_family: Option<String>,
+    /// in pixels; boxes and rows grow with it
+    pub font_size: isize,
+    /// to measure text with; should match `font_family`
+    pub font_metrics: FontMetrics,
+    pub text_color: String,
+    pub line_color: String,
+    /// in pixels
+    pub line_width: usize,
+    /// behind the whole diagram, transparent if `None`
+    pub background: Option<String>,
+    /// behind notes, labels and activation bars, so lines do not cross them
+    pub fill: String,
+    /// between the text and the edges of actor boxes and around labels
+    pub padding: isize,
+    /// between the text and the edges of notes and the title
+    pub note_padding: isize,
+    /// in pixels; longer messages, notes and actor names are wrapped
+    pub max_text_width: Option<isize>,
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self {
+            font_family: Some("DejaVu Sans, sans-serif".to_string()),
+            font_size: 16,
+            font_metrics: FontMetrics::dejavu_sans(),
+            text_color: "#000".to_string(),
+            line_color: "#000".to_string(),
+            line_width: 2,
+            background: None,
+            fill: "white".to_string(),
+            padding: 10,
+            note_padding: 5,
+            max_text_width: None,
+        }
+    }
+}
+
+impl Theme {
+    /// Names of the built-in themes, as used by `theme <name>`.
+    pub const NAMES: &'static [&'static str] = &["default", "dark", "neutral", "blueprint"];
+
+    /// Light text on a dark background.
+    pub fn dark() -> Self {
+        Self {
+            text_color: "#e6e6e6".to_string(),
+            line_color: "#c8c8c8".to_string(),
+            background: Some("#1e1e1e".to_string()),
+            fill: "#1e1e1e".to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Smaller text and grey, thinner lines, for printed documents.
+    pub fn neutral() -> Self {
+        Self {
+            font_size: 14,
+            text_color: "#333".to_string(),
+            line_color: "#666".to_string(),
+            line_width: 1,
+            background: Some("white".to_string()),
+            ..Self::default()
+        }
+    }
+
+    /// White on blue.
+    pub fn blueprint() -> Self {
+        Self {
+            font_family: Some("DejaVu Sans Mono, monospace".to_string()),
+            font_metrics: FontMetrics::dejavu_sans_mono(),
+            text_color: "white".to_string(),
+            line_color: "white".to_string(),
+            line_width: 1,
+            background: Some("#1f4e8c".to_string()),
+            fill: "#1f4e8c".to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// The built-in theme called `name`, one of [`Theme::NAMES`].
+    pub fn named(name: &str) -> Option<Self> {
+        match name {
+            "default" => Some(Self::default()),
+            "dark" => Some(Self::dark()),
+            "neutral" => Some(Self::neutral()),
+            "blueprint" => Some(Self::blueprint()),
+            _ => None,
+        }
+    }
+
+    /// Change `property`, named as in `theme <property> <value>`. Returns
+    /// `false`, leaving the theme as it was, if either is not understood.
+    ///
+    /// Setting `font-family` also switches to its bundled [`FontMetrics`] if
+    /// there are any, and keeps the current ones otherwise. Sizes are
+    /// clamped to [`Theme::size_range`].
+    pub fn set(&mut self, property: &str, value: &str) -> bool {
+        let size = || {
+            let range = Self::size_range(property)?;
+            let size = value.parse::<isize>().ok()?;
+            Some(size.clamp(*range.start(), *range.end()))
+        };
+        match property {
+            "font-family" => {
+                if let Some(font_metrics) = FontMetrics::named(value) {
+                    self.font_metrics = font_metrics;
+                }
+                self.font_family = Some(value.to_string());
+            }
+            "font-size" => match size() {
+                Some(size) => self.font_size = size,
+                None => return false,
+            },
+            "text-color" => self.text_color = value.to_string(),
+            "line-color" => self.line_color = value.to_string(),
+            "line-width" => match size() {
+                Some(size) => self.line_width = size as usize,
+                None => return false,
+            },
+            "background" if value == "none" => self.background = None,
+            "background" => self.background = Some(value.to_string()),
+            "fill" => self.fill = value.to_string(),
+            "padding" => match size() {
+                Some(size) => self.padding = size,
+                None => return false,
+            },
+            "note-padding" => match size() {
+                Some(size) => self.note_padding = size,
+                None => return false,
+            },
+            "max-text-width" if value == "none" => self.max_text_width = None,
+            "max-text-width" => match size() {
+                Some(size) => self.max_text_width = Some(size),
+                None => return false,
+            },
+            _ => return false,
+        }
+        true
+    }
+
+    /// Smallest and largest value of a size `property`, in pixels, or `None`
+    /// if it is not a size. `font-size`, `padding` and `note-padding` go up
+    /// to 1000, `line-width` to 100 and `max-text-width` to 10000.
+    pub fn size_range(property: &str) -> Option<RangeInclusive<isize>> {
+        SIZES
+            .iter()
+            .find(|(name, _)| *name == property)
+            .map(|(_, range)| range.clone())
+    }
+
+    pub(crate) fn apply(&mut self, directive: &ThemeDirective<'_>) {
+        match directive {
+            ThemeDirective::Named(name, _) => {
+                if let Some(theme) = Theme::named(name) {
+                    *self = theme;
+                }
+            }
+            ThemeDirective::Set(property, value, _) => {
+                self.set(property, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes_are_clamped() {
+        let mut theme = Theme::default();
+        assert!(theme.set("padding", "9223372036854775807"));
+        assert_eq!(theme.padding, 1000);
+        assert!(theme.set("font-size", "0"));
+        assert_eq!(theme.font_size, 1);
+        assert!(theme.set("line-width", "-3"));
+        assert_eq!(theme.line_width, 1);
+        assert!(theme.set("max-text-width", "200"));
+        assert_eq!(theme.max_text_width, Some(200));
+        assert!(theme.set("max-text-width", "none"));
+        assert_eq!(theme.max_text_width, None);
+    }
+
+    #[test]
+    fn sizes_must_be_numbers() {
+        let mut theme = Theme::default();
+        assert!(!theme.set("padding", "wide"));
+        assert!(!theme.set("font-size", "99999999999999999999"));
+        assert_eq!(theme, Theme::default());
+    }
+}
@@ -0,0 +1,418 @@
+//! Functions exported to JavaScript, for pages other than the editor.
+//!
+//! Errors are thrown as an `Error` whose `errors` property lists each
+//! problem as `{ message, line, column, endLine, endColumn }`.
+
+use js_sys::{Array, Object, Reflect, Uint8Array};
+use wasm_bindgen::prelude::*;
+
+use crate::share::{pickle, unpickle};
+use crate::{
+    Activate, Activation, Actor, ArrowType, Autonumber, Deactivate, Diagnostics, Document,
+    FontMetrics, Fragment, LineType, Note, Participant, RenderOptions, Signal, Span, Statement,
+    Style, StyleKind, SvgBackend, SvgSize, Theme, ThemeDirective, Title, Visitor,
+};
+
+fn set(object: &Object, key: &str, value: impl Into<JsValue>) {
+    Reflect::set(object, &JsValue::from_str(key), &value.into()).unwrap();
+}
+
+fn diagnostics_error(diagnostics: Diagnostics) -> JsValue {
+    let errors = Array::new();
+    for error in &diagnostics {
+        let (end_line, end_column) = error.span().end_line_col();
+        let object = Object::new();
+        set(&object, "message", error.reason());
+        set(&object, "line", error.line() as u32);
+        set(&object, "column", error.column() as u32);
+        set(&object, "endLine", end_line as u32);
+        set(&object, "endColumn", end_column as u32);
+        errors.push(&object);
+    }
+    let e = js_sys::Error::new(&diagnostics.to_string());
+    set(&e, "errors", errors);
+    e.into()
+}
+
+fn render_options(options: Option<Object>) -> Result<RenderOptions, JsValue> {
+    let mut render_options = RenderOptions::default();
+    let options = match options {
+        Some(options) => options,
+        None => return Ok(render_options),
+    };
+
+    let style = Reflect::get(&options, &JsValue::from_str("style"))?;
+    if let Some(style) = style.as_string() {
+        let style = match style.as_str() {
+            "handdrawn" => StyleKind::HandDrawn,
+            "clean" => StyleKind::Clean,
+            "rounded" => StyleKind::Rounded,
+            _ => return Err(js_sys::Error::new(&format!("unknown style `{}`", style)).into()),
+        };
+        render_options = render_options.with_style(style);
+    }
+
+    let theme = Reflect::get(&options, &JsValue::from_str("theme"))?;
+    let mut theme = if theme.is_undefined() {
+        Theme::default()
+    } else {
+        theme_option(&theme)?
+    };
+
+    let font = Reflect::get(&options, &JsValue::from_str("font"))?;
+    if !font.is_undefined() {
+        let font = Uint8Array::new(&font).to_vec();
+        let metrics = FontMetrics::from_font(&font)
+            .map_err(|e| JsValue::from(js_sys::Error::new(&e.to_string())))?;
+        if let Some(family) = metrics.family() {
+            theme.font_family = Some(format!("\"{}\"", family));
+        }
+        theme.font_metrics = metrics;
+    }
+    Ok(render_options.with_theme(theme))
+}
+
+/// `theme` is the name of a built-in theme, or an object with the properties
+/// to change as keys, based on the theme named by its `name` key if any.
+fn theme_option(theme: &JsValue) -> Result<Theme, JsValue> {
+    let unknown = |name: &str| js_sys::Error::new(&format!("unknown theme `{}`", name)).into();
+    if let Some(name) = theme.as_string() {
+        return Theme::named(&name).ok_or_else(|| unknown(&name));
+    }
+
+    let object = theme.dyn_ref::<Object>().ok_or_else(|| unknown("?"))?;
+    let name = Reflect::get(object, &JsValue::from_str("name"))?;
+    let mut result = match name.as_string() {
+        Some(name) => Theme::named(&name).ok_or_else(|| unknown(&name))?,
+        None => Theme::default(),
+    };
+    for entry in Object::entries(object).iter() {
+        let entry = Array::from(&entry);
+        let property = entry.get(0).as_string().unwrap_or_default();
+        let value = entry.get(1);
+        let value = value
+            .as_string()
+            .or_else(|| value.as_f64().map(|n| n.to_string()))
+            .unwrap_or_default();
+        if property != "name" && !result.set(&property, &value) {
+            let message = format!("cannot set theme `{}` to `{}`", property, value);
+            return Err(js_sys::Error::new(&message).into());
+        }
+    }
+    Ok(result)
+}
+
+fn svg_backend(text: &str, options: Option<&Object>) -> Result<SvgBackend, JsValue> {
+    let mut backend = SvgBackend::new().with_source(text);
+    let options = match options {
+        Some(options) => options,
+        None => return Ok(backend),
+    };
+
+    let id_prefix = Reflect::get(options, &JsValue::from_str("idPrefix"))?;
+    if let Some(id_prefix) = id_prefix.as_string() {
+        backend = backend.with_id_prefix(id_prefix);
+    }
+    let size = Reflect::get(options, &JsValue::from_str("size"))?;
+    if let Some(size) = size.as_string() {
+        let size = match size.as_str() {
+            "intrinsic" => SvgSize::Intrinsic,
+            "fit-width" => SvgSize::FitWidth,
+            _ => return Err(js_sys::Error::new(&format!("unknown size `{}`", size)).into()),
+        };
+        backend = backend.with_size(size);
+    } else if let Some(size) = size.dyn_ref::<Object>() {
+        let width = Reflect::get(size, &JsValue::from_str("width"))?.as_f64();
+        let height = Reflect::get(size, &JsValue::from_str("height"))?.as_f64();
+        match (width, height) {
+            (Some(width), Some(height)) => {
+                backend = backend.with_size(SvgSize::Fixed(width as f32, height as f32));
+            }
+            _ => return Err(js_sys::Error::new("size needs a `width` and a `height`").into()),
+        }
+    }
+    if let Some(zoom) = Reflect::get(options, &JsValue::from_str("zoom"))?.as_f64() {
+        backend = backend.with_zoom(zoom as f32);
+    }
+    Ok(backend)
+}
+
+/// Render `text` to an SVG string, which keeps `text` for `sourceFromSvg`.
+#[wasm_bindgen(js_name = render)]
+pub fn render(text: &str, options: Option<Object>) -> Result<String, JsValue> {
+    let document = crate::parse(text).map_err(diagnostics_error)?;
+    let backend = svg_backend(text, options.as_ref())?;
+    let paper = crate::draw(&document, &render_options(options)?);
+    Ok(paper.render(backend))
+}
+
+/// The text an SVG made by `render` was drawn from.
+#[wasm_bindgen(js_name = sourceFromSvg)]
+pub fn source_from_svg(svg: &str) -> Result<String, JsValue> {
+    crate::source_from_svg(svg).map_err(|e| js_sys::Error::new(&e.to_string()).into())
+}
+
+#[cfg(feature = "png")]
+fn png_options(options: Option<&Object>) -> Result<crate::PngOptions, JsValue> {
+    let mut png_options = crate::PngOptions::new();
+    let options = match options {
+        Some(options) => options,
+        None => return Ok(png_options),
+    };
+
+    if let Some(scale) = Reflect::get(options, &JsValue::from_str("scale"))?.as_f64() {
+        png_options = png_options.with_scale(scale as f32);
+    }
+    let background = Reflect::get(options, &JsValue::from_str("background"))?;
+    if let Some(background) = background.as_string() {
+        let background = match background.as_str() {
+            "theme" => crate::Background::Theme,
+            "transparent" => crate::Background::Transparent,
+            _ => crate::Background::Color(background),
+        };
+        png_options = png_options.with_background(background);
+    }
+    let font = Reflect::get(options, &JsValue::from_str("font"))?;
+    if !font.is_undefined() {
+        png_options = png_options.with_font(Uint8Array::new(&font).to_vec());
+    }
+    Ok(png_options)
+}
+
+/// Render `text` to PNG bytes.
+#[cfg(feature = "png")]
+#[wasm_bindgen(js_name = renderPng)]
+pub fn render_png(text: &str, options: Option<Object>) -> Result<Vec<u8>, JsValue> {
+    let document = crate::parse(text).map_err(diagnostics_error)?;
+    let png_options = png_options(options.as_ref())?;
+    crate::render_png(&document, &render_options(options)?, &png_options)
+        .map_err(|e| js_sys::Error::new(&e.to_string()).into())
+}
+
+/// Parse `text` into a tree of plain objects, each with a `type` and a `span`.
+#[wasm_bindgen(js_name = parse)]
+pub fn parse(text: &str) -> Result<JsValue, JsValue> {
+    let document = crate::parse(text).map_err(diagnostics_error)?;
+    Ok(document.accept(&JsonAst, &mut ()))
+}
+
+/// The URL hash the editor uses to share `text`.
+#[wasm_bindgen(js_name = encodeShareHash)]
+pub fn encode_share_hash(text: &str) -> String {
+    pickle(text)
+}
+
+/// The text shared with `hash`, as made by `encodeShareHash`.
+#[wasm_bindgen(js_name = decodeShareHash)]
+pub fn decode_share_hash(hash: &str) -> Result<String, JsValue> {
+    unpickle(hash).map_err(|e| js_sys::Error::new(&e.to_string()).into())
+}
+
+/// Builds the object tree returned by [`parse`].
+#[derive(Debug)]
+struct JsonAst;
+
+impl JsonAst {
+    fn node(&self, kind: &str, span: Span) -> Object {
+        let (start_line, start_column) = span.start_line_col();
+        let (end_line, end_column) = span.end_line_col();
+        let location = Object::new();
+        set(&location, "line", start_line as u32);
+        set(&location, "column", start_column as u32);
+        set(&location, "endLine", end_line as u32);
+        set(&location, "endColumn", end_column as u32);
+
+        let object = Object::new();
+        set(&object, "type", kind);
+        set(&object, "span", location);
+        object
+    }
+
+    fn actor(&self, actor: &Actor<'_>) -> JsValue {
+        actor.as_ref().into()
+    }
+
+    fn statements(&self, statements: &[Statement<'_>]) -> Array {
+        statements
+            .iter()
+            .map(|statement| statement.accept(self, &mut ()))
+            .collect()
+    }
+}
+
+impl<'i> Visitor<'i> for JsonAst {
+    type Output = JsValue;
+    type Context = ();
+
+    fn default_action(&self, _ctx: &mut Self::Context) -> Self::Output {
+        JsValue::NULL
+    }
+
+    fn visit_document(&self, document: &Document<'i>, _ctx: &mut Self::Context) -> Self::Output {
+        let object = Object::new();
+        set(&object, "type", "document");
+        let statements = document
+            .iter()
+            .map(|statement| statement.accept(self, &mut ()))
+            .collect::<Array>();
+        set(&object, "statements", statements);
+        object.into()
+    }
+
+    fn visit_statement(&self, statement: &Statement<'i>, ctx: &mut Self::Context) -> Self::Output {
+        match statement {
+            Statement::Title(title) => title.accept(self, ctx),
+            Statement::Signal(signal) => signal.accept(self, ctx),
+            Statement::Participant(participant) => participant.accept(self, ctx),
+            Statement::Note(note) => note.accept(self, ctx),
+            Statement::Activate(activate) => activate.accept(self, ctx),
+            Statement::Deactivate(deactivate) => deactivate.accept(self, ctx),
+            Statement::Fragment(fragment) => fragment.accept(self, ctx),
+            Statement::Autonumber(autonumber) => autonumber.accept(self, ctx),
+            Statement::Style(style) => style.accept(self, ctx),
+            Statement::Theme(theme) => theme.accept(self, ctx),
+        }
+    }
+
+    fn visit_title(&self, title: &Title<'i>, _ctx: &mut Self::Context) -> Self::Output {
+        let object = self.node("title", title.span());
+        set(&object, "text", title.as_ref());
+        object.into()
+    }
+
+    fn visit_signal(&self, signal: &Signal<'i>, _ctx: &mut Self::Context) -> Self::Output {
+        let object = self.node("signal", signal.span());
+        set(&object, "from", self.actor(signal.from()));
+        set(&object, "to", self.actor(signal.to()));
+        let line = match signal.signal().line_type() {
+            LineType::Normal => "normal",
+            LineType::Dot => "dot",
+        };
+        set(&object, "line", line);
+        let arrow = |arrow_type| match arrow_type {
+            ArrowType::None => "none",
+            ArrowType::Normal => "normal",
+            ArrowType::Open => "open",
+        };
+        set(
+            &object,
+            "arrowStart",
+            arrow(signal.signal().start_arrow_type()),
+        );
+        set(&object, "arrow", arrow(signal.signal().arrow_type()));
+        let activation = match signal.signal().activation() {
+            Some(Activation::Activate) => JsValue::from_str("activate"),
+            Some(Activation::Deactivate) => JsValue::from_str("deactivate"),
+            None => JsValue::NULL,
+        };
+        set(&object, "activation", activation);
+        set(&object, "message", signal.message());
+        object.into()
+    }
+
+    fn visit_participant(
+        &self,
+        participant: &Participant<'i>,
+        _ctx: &mut Self::Context,
+    ) -> Self::Output {
+        let object = self.node("participant", participant.span());
+        set(&object, "name", self.actor(participant.actor()));
+        let display_name = match participant.display_name() {
+            Some(actor) => self.actor(actor),
+            None => JsValue::NULL,
+        };
+        set(&object, "displayName", display_name);
+        object.into()
+    }
+
+    fn visit_note(&self, note: &Note<'i>, _ctx: &mut Self::Context) -> Self::Output {
+        let object = self.node("note", note.span());
+        let placement = match note {
+            Note::LeftOf(..) => "leftOf",
+            Note::RightOf(..) => "rightOf",
+            Note::Over(..) => "over",
+        };
+        set(&object, "placement", placement);
+        let actors = note
+            .actors()
+            .into_iter()
+            .map(|actor| self.actor(actor))
+            .collect::<Array>();
+        set(&object, "actors", actors);
+        set(&object, "message", note.message());
+        object.into()
+    }
+
+    fn visit_activate(&self, activate: &Activate<'i>, _ctx: &mut Self::Context) -> Self::Output {
+        let object = self.node("activate", activate.span());
+        set(&object, "actor", self.actor(activate.actor()));
+        object.into()
+    }
+
+    fn visit_deactivate(
+        &self,
+        deactivate: &Deactivate<'i>,
+        _ctx: &mut Self::Context,
+    ) -> Self::Output {
+        let object = self.node("deactivate", deactivate.span());
+        set(&object, "actor", self.actor(deactivate.actor()));
+        object.into()
+    }
+
+    fn visit_fragment(&self, fragment: &Fragment<'i>, _ctx: &mut Self::Context) -> Self::Output {
+        let object = self.node("fragment", fragment.span());
+        set(&object, "kind", fragment.kind().as_ref());
+        let sections = fragment
+            .sections()
+            .iter()
+            .map(|section| {
+                let object = self.node("section", section.span());
+                let guard = match section.guard() {
+                    Some(guard) => JsValue::from_str(guard),
+                    None => JsValue::NULL,
+                };
+                set(&object, "guard", guard);
+                set(&object, "statements", self.statements(section.statements()));
+                object
+            })
+            .collect::<Array>();
+        set(&object, "sections", sections);
+        object.into()
+    }
+
+    fn visit_autonumber(
+        &self,
+        autonumber: &Autonumber<'i>,
+        _ctx: &mut Self::Context,
+    ) -> Self::Output {
+        let object = self.node("autonumber", autonumber.span());
+        match autonumber {
+            Autonumber::On(start, step, format, _) => {
+                set(&object, "on", true);
+                set(&object, "start", start.map(|n| n as f64));
+                set(&object, "step", step.map(|n| n as f64));
+                set(&object, "format", format.as_deref());
+            }
+            Autonumber::Off(_) => set(&object, "on", false),
+        }
+        object.into()
+    }
+
+    fn visit_style(&self, style: &Style, _ctx: &mut Self::Context) -> Self::Output {
+        let object = self.node("style", style.span());
+        set(&object, "style", style.kind().as_ref());
+        object.into()
+    }
+
+    fn visit_theme(&self, theme: &ThemeDirective<'i>, _ctx: &mut Self::Context) -> Self::Output {
+        let object = self.node("theme", theme.span());
+        match theme {
+            ThemeDirective::Named(name, _) => set(&object, "name", name.as_ref()),
+            ThemeDirective::Set(property, value, _) => {
+                set(&object, "property", property.as_ref());
+                set(&object, "value", value.as_ref());
+            }
+        }
+        object.into()
+    }
+}
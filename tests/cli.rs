@@ -0,0 +1,160 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// An empty directory of its own for `test`.
+fn scratch(test: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("seqdia-{}-{}", test, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+fn seqdia(dir: &Path, args: &[&str]) {
+    let status = Command::new(env!("CARGO_BIN_EXE_seqdia"))
+        .current_dir(dir)
+        .args(args)
+        .status()
+        .unwrap();
+    assert!(status.success(), "seqdia {:?} failed", args);
+}
+
+/// Run with `args`, which must fail, checking that none of the files
+/// `inputs` is changed.
+fn refuses(dir: &Path, args: &[&str], inputs: &[&str]) {
+    let before = inputs
+        .iter()
+        .map(|input| fs::read(dir.join(input)).unwrap())
+        .collect::<Vec<_>>();
+    let status = Command::new(env!("CARGO_BIN_EXE_seqdia"))
+        .current_dir(dir)
+        .args(args)
+        .stderr(Stdio::null())
+        .status()
+        .unwrap();
+    assert!(!status.success(), "seqdia {:?} succeeded", args);
+    for (input, before) in inputs.iter().zip(before) {
+        assert_eq!(
+            fs::read(dir.join(input)).unwrap(),
+            before,
+            "{} changed",
+            input
+        );
+    }
+}
+
+/// Run with `args` on the files `inputs`, checking that none of them is
+/// changed and returning the files there are afterwards.
+fn keeps_inputs(dir: &Path, args: &[&str], inputs: &[&str]) -> Vec<String> {
+    let before = inputs
+        .iter()
+        .map(|input| fs::read(dir.join(input)).unwrap())
+        .collect::<Vec<_>>();
+    seqdia(dir, &[args, inputs].concat());
+    for (input, before) in inputs.iter().zip(before) {
+        assert_eq!(
+            fs::read(dir.join(input)).unwrap(),
+            before,
+            "{} changed",
+            input
+        );
+    }
+    let mut files = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect::<Vec<_>>();
+    files.sort();
+    files
+}
+
+#[test]
+fn text_inputs_are_not_overwritten() {
+    let dir = scratch("text");
+    fs::write(dir.join("a.txt"), "A -> B: a\n").unwrap();
+    fs::write(dir.join("b.txt"), "B -> A: b\n").unwrap();
+
+    let files = keeps_inputs(&dir, &["-f", "text"], &["a.txt", "b.txt"]);
+    assert_eq!(files, ["a.seqdia.txt", "a.txt", "b.seqdia.txt", "b.txt"]);
+    let files = keeps_inputs(&dir, &["-f", "source", "-o", "."], &["a.txt", "b.txt"]);
+    assert_eq!(files, ["a.seqdia.txt", "a.txt", "b.seqdia.txt", "b.txt"]);
+    assert_eq!(
+        fs::read_to_string(dir.join("a.seqdia.txt")).unwrap(),
+        "A -> B: a\n"
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn svg_inputs_are_not_overwritten() {
+    let dir = scratch("svg");
+    fs::write(dir.join("a.txt"), "A -> B: a\n").unwrap();
+    fs::write(dir.join("b.txt"), "B -> A: b\n").unwrap();
+    seqdia(&dir, &["a.txt", "b.txt"]);
+
+    let files = keeps_inputs(&dir, &[], &["a.svg", "b.svg"]);
+    assert_eq!(
+        files,
+        [
+            "a.seqdia.svg",
+            "a.svg",
+            "a.txt",
+            "b.seqdia.svg",
+            "b.svg",
+            "b.txt"
+        ]
+    );
+    // drawn from the source kept in the SVG
+    assert_eq!(
+        fs::read(dir.join("a.seqdia.svg")).unwrap(),
+        fs::read(dir.join("a.svg")).unwrap()
+    );
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn single_output_is_not_the_input() {
+    let dir = scratch("single");
+    fs::write(dir.join("a.txt"), "A -> B: a\n").unwrap();
+    refuses(&dir, &["a.txt", "-o", "a.txt"], &["a.txt"]);
+    refuses(&dir, &["-f", "text", "a.txt", "-o", "./a.txt"], &["a.txt"]);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn outputs_are_not_other_inputs() {
+    let dir = scratch("other");
+    fs::write(dir.join("a.txt"), "A -> B: a\n").unwrap();
+    seqdia(&dir, &["a.txt", "-o", "a.svg"]);
+    // the diagram drawn from `a.txt` would go to `a.svg`, read after it
+    refuses(&dir, &["a.txt", "a.svg"], &["a.txt", "a.svg"]);
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn outputs_do_not_collide() {
+    let dir = scratch("collide");
+    fs::write(dir.join("a.txt"), "A -> B: a\n").unwrap();
+    fs::write(dir.join("a.md"), "B -> A: b\n").unwrap();
+    refuses(&dir, &["a.txt", "a.md"], &["a.txt", "a.md"]);
+    assert!(!dir.join("a.svg").exists());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn outputs_into_a_directory_do_not_collide() {
+    let dir = scratch("directory");
+    for sub in ["x", "y", "out"] {
+        fs::create_dir(dir.join(sub)).unwrap();
+    }
+    fs::write(dir.join("x/a.txt"), "A -> B: a\n").unwrap();
+    fs::write(dir.join("y/a.txt"), "B -> A: b\n").unwrap();
+    refuses(
+        &dir,
+        &["x/a.txt", "y/a.txt", "-o", "out"],
+        &["x/a.txt", "y/a.txt"],
+    );
+    assert!(!dir.join("out/a.svg").exists());
+    seqdia(&dir, &["x/a.txt", "y/a.txt"]);
+    assert!(dir.join("x/a.svg").exists() && dir.join("y/a.svg").exists());
+    fs::remove_dir_all(dir).unwrap();
+}
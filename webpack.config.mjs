@@ -42,6 +42,7 @@ const config = {
     }),
     new WasmPackPlugin({
       crateDirectory: import.meta.dirname,
+      extraArgs: "-- --features editor",
     }),
   ],
 };